@@ -0,0 +1,65 @@
+//! Feeds arbitrary inputs to every `Sorter` through its step loop.
+//! The first byte picks the algorithm, the rest is the array to sort.
+//!
+//! Run with `cargo +nightly fuzz run sorter_steps` from the repository root.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sorting_visualizer::algorithms::input_limit;
+use sorting_visualizer::params::Settings;
+use sorting_visualizer::sound;
+use sorting_visualizer::types::Algorithms;
+use strum::IntoEnumIterator;
+
+/// Longest array a run gets, so slow algorithms still finish quickly.
+const MAX_LEN: usize = 64;
+
+/// Steps after which a run is considered stuck.
+const MAX_STEPS: usize = 1_000_000;
+
+fuzz_target!(|data: (u8, Vec<u16>)| {
+    let (selector, values) = data;
+    let algorithms: Vec<Algorithms> = Algorithms::iter().collect();
+    let algorithm = algorithms[selector as usize % algorithms.len()];
+    let mut sorter = Settings::default().create_sorter(algorithm);
+
+    let mut input: Vec<usize> = values.into_iter().map(usize::from).collect();
+    input.truncate(input_limit(sorter.as_ref(), input.len().min(MAX_LEN)));
+    let mut array = input.clone();
+    let len = array.len();
+
+    let mut steps = 0;
+    sound::muted(|| loop {
+        steps += 1;
+        assert!(steps <= MAX_STEPS, "no result after {MAX_STEPS} steps");
+        let finished = sorter.step(&mut array);
+        let (a, b) = sorter.special();
+        assert!(
+            [a, b].iter().all(|&i| i < len || i == usize::MAX),
+            "special() is {:?} for {len} elements",
+            (a, b)
+        );
+        assert_eq!(
+            sorter.is_finished(),
+            finished,
+            "is_finished() disagrees with step()"
+        );
+        if finished {
+            assert!(sorter.step(&mut array), "step() unfinished again");
+            break;
+        }
+    });
+
+    let mut expected = input;
+    expected.sort_unstable();
+    let mut permutation = array.clone();
+    permutation.sort_unstable();
+    assert_eq!(permutation, expected, "not a permutation");
+    let (low, high) = if algorithm.is_partial() {
+        sorter.target().unwrap_or((0, 0))
+    } else {
+        (0, len)
+    };
+    assert_eq!(array[low..high], expected[low..high], "not sorted");
+});
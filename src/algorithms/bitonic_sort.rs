@@ -0,0 +1,96 @@
+use super::sorting_network::{bitonic_layers, NetworkRunner};
+use super::structure::Structure;
+use super::{Reasons, Sorter};
+use std::borrow::Cow;
+
+/// Represents Bitonic Sort, executed as a sorting network.
+/// Each step runs one layer of comparators, all of which touch disjoint elements.
+pub struct BitonicSort {
+    network: Option<NetworkRunner>, // The network, built on the first step once the length is known.
+    wires: usize,                   // Number of elements the network was built for.
+    finished: bool,                 // Indicates whether the sorting is complete.
+    swaps: usize,
+    comparisons: usize,
+    writes: usize,
+}
+
+impl Sorter for BitonicSort {
+    /// Creates a new instance of BitonicSort.
+    fn new() -> Self {
+        BitonicSort {
+            network: None,
+            wires: 0,
+            finished: false,
+            swaps: 0,
+            comparisons: 0,
+            writes: 0,
+        }
+    }
+
+    /// Returns no pair, since a whole layer is active at once; see `highlights()`.
+    fn special(&self) -> (usize, usize) {
+        (usize::MAX, usize::MAX)
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        Reasons::Comparing
+    }
+
+    /// Executes one layer of the bitonic sorting network.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+
+        let network = self.network.get_or_insert_with(|| {
+            self.wires = array.len();
+            NetworkRunner::new(bitonic_layers(array.len()))
+        });
+        self.finished = network.step(
+            array,
+            &mut self.comparisons,
+            &mut self.swaps,
+            &mut self.writes,
+        );
+        self.finished
+    }
+
+    /// Resets the state of the BitonicSort instance for a fresh sort.
+    fn reset_state(&mut self) {
+        *self = Self::new(); // Reset all fields to their initial state.
+    }
+
+    /// Checks if the sorting process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Returns every element touched by the current layer.
+    fn highlights(&self) -> Vec<(usize, Reasons)> {
+        self.network
+            .as_ref()
+            .map_or_else(Vec::new, |network| network.highlights().to_vec())
+    }
+
+    /// Returns the network diagram.
+    fn structure(&self) -> Option<Structure<'_>> {
+        self.network.as_ref().map(|network| Structure::Network {
+            wires: self.wires,
+            layers: Cow::Borrowed(network.layers()),
+            current: network.current(),
+        })
+    }
+}
@@ -0,0 +1,351 @@
+use super::subrange::{lower_bound, rotate, RangeInsertion, RangeRotationMerge};
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+
+/// Length of the runs sorted with insertion sort before merging starts, by default.
+pub const DEFAULT_INSERTION_RUN: usize = 8;
+
+/// Swaps the `len` elements starting at `a` with the `len` elements starting at `b`.
+fn block_swap(
+    array: &mut [usize],
+    a: usize,
+    b: usize,
+    len: usize,
+    swaps: &mut usize,
+    writes: &mut usize,
+) {
+    if a == b {
+        return;
+    }
+    for offset in 0..len {
+        array.swap(a + offset, b + offset);
+    }
+    *swaps += len;
+    *writes += 2 * len;
+}
+
+/// Stepwise block merge of the sorted runs `A = start..mid` and `B = mid..end` in O(1) memory,
+/// following the buffer-less path of WikiSort.
+/// `A` is split into blocks of about √|A| elements, which are rolled through `B`:
+/// whenever the smallest remaining `A` block belongs before the next `B` values it is dropped
+/// into place, and the previously dropped block is merged locally with the `B` values behind it.
+struct BlockMerge {
+    block: usize,                      // Size of the A blocks.
+    end: usize,                        // End of B.
+    block_a: (usize, usize),           // Range of the A blocks still rolling.
+    block_b: (usize, usize),           // The next B block to roll past.
+    last_a: (usize, usize),            // The A block dropped most recently.
+    last_b: (usize, usize),            // The B values following `last_a`.
+    min_a: usize,                      // Start of the smallest rolling A block.
+    local: Option<RangeRotationMerge>, // Local merge in progress, if any.
+    rolling: bool,                     // Whether A blocks are still rolling.
+    final_merge: bool,                 // Whether the last local merge has been started.
+    focus: (usize, usize),             // Bounds of the range touched by the last step.
+}
+
+impl BlockMerge {
+    /// Prepares the block merge of `start..mid` and `mid..end`.
+    fn new(start: usize, mid: usize, end: usize) -> Self {
+        let block = (mid - start).isqrt().max(1);
+        // The first A block takes the remainder so the rest split evenly.
+        let first_a = (start, start + (mid - start) % block);
+        BlockMerge {
+            block,
+            end,
+            block_a: (first_a.1, mid),
+            block_b: (mid, (mid + block).min(end)),
+            last_a: first_a,
+            last_b: (first_a.1, first_a.1),
+            min_a: first_a.1,
+            local: None,
+            rolling: true,
+            final_merge: false,
+            focus: (start, end - 1),
+        }
+    }
+
+    /// Finds the smallest rolling A block, breaking ties on the first value by the last one.
+    fn find_min_a(&mut self, array: &[usize], comparisons: &mut usize) {
+        self.min_a = self.block_a.0;
+        let mut start = self.block_a.0 + self.block;
+        while start < self.block_a.1 {
+            let (first, min_first) = (array[start], array[self.min_a]);
+            *comparisons += 1;
+            let smaller = if first == min_first {
+                *comparisons += 1;
+                array[start + self.block - 1] < array[self.min_a + self.block - 1]
+            } else {
+                first < min_first
+            };
+            if smaller {
+                self.min_a = start;
+            }
+            start += self.block;
+        }
+    }
+
+    /// Performs one rolling move or one step of a local merge.
+    /// # Returns
+    /// `true` once both runs are merged.
+    fn step(
+        &mut self,
+        array: &mut [usize],
+        comparisons: &mut usize,
+        swaps: &mut usize,
+        writes: &mut usize,
+    ) -> bool {
+        if let Some(local) = &mut self.local {
+            let done = local.step(array, comparisons, swaps, writes);
+            self.focus = local.focus();
+            if done {
+                self.local = None;
+            }
+            return false;
+        }
+
+        if !self.rolling {
+            if self.final_merge {
+                return true;
+            }
+            // Merge the last dropped A block with every B value that follows it.
+            self.final_merge = true;
+            self.local = Some(RangeRotationMerge::new(
+                self.last_a.0,
+                self.last_a.1,
+                self.end,
+            ));
+            self.focus = (self.last_a.0, self.end - 1);
+            return false;
+        }
+
+        let block = self.block;
+        let b_len = self.block_b.1 - self.block_b.0;
+        let drop = b_len == 0 || {
+            self.last_b.1 > self.last_b.0 && {
+                *comparisons += 1;
+                array[self.last_b.1 - 1] >= array[self.min_a]
+            }
+        };
+
+        if drop {
+            // B values from the split on belong after the smallest A block.
+            let split = lower_bound(
+                array,
+                self.last_b.0,
+                self.last_b.1,
+                array[self.min_a],
+                comparisons,
+            );
+            let remaining = self.block_a.0 - split;
+            block_swap(array, self.block_a.0, self.min_a, block, swaps, writes);
+            rotate(
+                array,
+                split,
+                self.block_a.0 + block,
+                remaining,
+                swaps,
+                writes,
+            );
+
+            // The previous A block still has to be merged with the B values before the split.
+            self.local = Some(RangeRotationMerge::new(self.last_a.0, self.last_a.1, split));
+            self.last_a = (split, split + block);
+            self.last_b = (self.last_a.1, self.last_a.1 + remaining);
+            self.focus = (split, self.block_a.0 + block - 1);
+            self.block_a.0 += block;
+            if self.block_a.0 == self.block_a.1 {
+                self.rolling = false;
+            } else {
+                self.find_min_a(array, comparisons);
+            }
+        } else if b_len < block {
+            // Move the short last B block in front of the remaining A blocks.
+            let a_len = self.block_a.1 - self.block_a.0;
+            rotate(array, self.block_a.0, self.block_b.1, a_len, swaps, writes);
+            self.focus = (self.block_a.0, self.block_b.1 - 1);
+            self.last_b = (self.block_a.0, self.block_a.0 + b_len);
+            self.block_a = (self.block_a.0 + b_len, self.block_a.1 + b_len);
+            self.min_a += b_len;
+            self.block_b = (self.block_b.1, self.block_b.1);
+        } else {
+            // Roll the leftmost A block to the back by swapping it with the next B block.
+            block_swap(array, self.block_a.0, self.block_b.0, block, swaps, writes);
+            self.focus = (self.block_a.0, self.block_b.1 - 1);
+            self.last_b = (self.block_a.0, self.block_a.0 + block);
+            if self.min_a == self.block_a.0 {
+                self.min_a = self.block_a.1;
+            }
+            self.block_a = (self.block_a.0 + block, self.block_a.1 + block);
+            self.block_b = (self.block_b.1, (self.block_b.1 + block).min(self.end));
+        }
+        play_beep();
+        false
+    }
+}
+
+/// Represents a block merge sort in the style of WikiSort and its state.
+/// Short runs are insertion sorted, then merged bottom-up with block merges that move
+/// √n-sized blocks around instead of copying through a buffer,
+/// so only a constant amount of extra memory is used.
+pub struct BlockMergeSort {
+    run: usize,                        // Length of the runs sorted with insertion sort.
+    insertion: Option<RangeInsertion>, // Insertion sort of the current run, if any.
+    next_run: usize,                   // Start of the next run to insertion sort.
+    width: usize,                      // Length of the runs merged in the current pass.
+    index: usize,                      // Start of the next pair of runs to merge.
+    merge: Option<BlockMerge>,         // Block merge in progress, if any.
+    special: (usize, usize),           // Indices touched by the last step.
+    reason: Reasons,                   // Reason for the current operation.
+    finished: bool,                    // Indicates whether the sorting is complete.
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+}
+
+impl BlockMergeSort {
+    /// Creates a BlockMergeSort that insertion sorts runs of `run` elements before merging.
+    pub fn with_run(run: usize) -> Self {
+        let run = run.max(1);
+        BlockMergeSort {
+            run,
+            insertion: None,
+            next_run: 0,
+            width: run,
+            index: 0,
+            merge: None,
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+}
+
+impl Sorter for BlockMergeSort {
+    /// Creates a new instance of BlockMergeSort.
+    fn new() -> Self {
+        Self::with_run(DEFAULT_INSERTION_RUN)
+    }
+
+    /// Returns the indices touched by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step: one insertion, one block move, or one local rotation.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+        let n = array.len();
+        let swaps = self.swaps;
+
+        // Insertion sort short runs first.
+        if self.next_run < n {
+            let (low, high) = (self.next_run, (self.next_run + self.run).min(n));
+            let insertion = self
+                .insertion
+                .get_or_insert_with(|| RangeInsertion::new(low, high));
+            let done = insertion.step(
+                array,
+                &mut self.comparisons,
+                &mut self.swaps,
+                &mut self.writes,
+            );
+            self.special = insertion.focus();
+            if done {
+                self.insertion = None;
+                self.next_run = high;
+            }
+        } else if let Some(merge) = &mut self.merge {
+            let done = merge.step(
+                array,
+                &mut self.comparisons,
+                &mut self.swaps,
+                &mut self.writes,
+            );
+            self.special = merge.focus;
+            if done {
+                self.merge = None;
+            }
+        } else {
+            // Move on to the next pass once no pair of runs is left in this one.
+            if self.index + self.width >= n {
+                self.width *= 2;
+                self.index = 0;
+                if self.width >= n {
+                    self.finished = true;
+                    self.special = (usize::MAX, usize::MAX);
+                    return true;
+                }
+            }
+
+            let start = self.index;
+            let mid = start + self.width;
+            let end = (mid + self.width).min(n);
+            self.index = end;
+
+            // Runs that are already in order need no merge.
+            self.comparisons += 1;
+            self.special = (mid - 1, mid);
+            if array[mid - 1] > array[mid] {
+                self.merge = Some(BlockMerge::new(start, mid, end));
+            }
+        }
+
+        self.reason = if self.swaps > swaps {
+            Reasons::Switching
+        } else {
+            Reasons::Comparing
+        };
+        false
+    }
+
+    /// Resets the state of the BlockMergeSort instance for a fresh sort.
+    fn reset_state(&mut self) {
+        *self = Self::with_run(self.run); // Keep the run length.
+    }
+
+    /// Checks if the sorting process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Returns whether runs are being insertion sorted or block merged.
+    fn phase(&self) -> Option<&'static str> {
+        if self.finished {
+            None
+        } else if self.insertion.is_some() {
+            Some("Insertion sorting short runs")
+        } else if self
+            .merge
+            .as_ref()
+            .is_some_and(|merge| merge.local.is_some())
+        {
+            Some("Local rotation merge")
+        } else if self.merge.is_some() {
+            Some("Rolling blocks")
+        } else {
+            Some("Block merge passes")
+        }
+    }
+}
@@ -1,4 +1,6 @@
+use super::permutation_sort::factorial;
 use super::{Reasons, Sorter};
+use crate::rng;
 use crate::sound::play_beep;
 use rand::prelude::SliceRandom; // Import the SliceRandom trait to shuffle the array
 
@@ -8,6 +10,7 @@ pub struct BogoSort {
     reason: Reasons, // Tracks the reason for the current action (either comparing or switching).
     swaps: usize,    // Indicates if the sorting is finished.
     comparisons: usize,
+    writes: usize,
 }
 
 impl BogoSort {
@@ -29,6 +32,7 @@ impl Sorter for BogoSort {
             reason: Reasons::Comparing, // Initial state is "Comparing" because BogoSort will check if it's sorted.
             swaps: 0,                   // Indicates if the sorting is finished.
             comparisons: 0,
+            writes: 0,
         }
     }
 
@@ -61,7 +65,8 @@ impl Sorter for BogoSort {
 
         // If the array is not sorted, shuffle it to attempt to sort it randomly.
         if !self.is_sorted {
-            array.shuffle(&mut rand::thread_rng()); // Shuffle the array randomly.
+            rng::with_rng(|rng| array.shuffle(rng)); // Shuffle the array randomly.
+            self.writes += 2 * array.len().saturating_sub(1); // A shuffle swaps every position once.
             self.reason = Reasons::Switching; // Indicate that elements have been shuffled (switched).
             play_beep();
             self.comparisons += 1;
@@ -69,13 +74,12 @@ impl Sorter for BogoSort {
             self.reason = Reasons::Comparing; // If sorted, set the reason to "Comparing" (though no comparisons are needed).
         }
 
-        false // Sorting is not complete yet, so return false.
+        self.is_sorted // Sorting is complete once the check finds the array sorted.
     }
 
     /// Resets the state of BogoSort for a fresh sort, making the array unsorted again.
     fn reset_state(&mut self) {
-        self.is_sorted = false; // Reset the sorting state to unsorted.
-        self.reason = Reasons::Comparing; // Reset the reason to "Comparing".
+        *self = Self::new(); // Reset all fields, counters included, to their initial state.
     }
 
     /// Returns whether the sorting process is complete.
@@ -90,4 +94,13 @@ impl Sorter for BogoSort {
     fn swaps(&self) -> usize {
         self.swaps
     }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// The expected number of shuffles for distinct values: one in n! is sorted.
+    fn estimated_steps(&self, len: usize) -> Option<f64> {
+        Some(factorial(len))
+    }
 }
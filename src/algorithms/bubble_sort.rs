@@ -9,7 +9,8 @@ pub struct BubbleSort {
     action_reason: Reasons, // Tracks the reason for the current action (Comparing or Switching).
     finished: bool,         // Indicates if the sorting is finished.
     comparisons: usize,     // counts the number of comparisons
-    swaps: usize,           // Indicates if the sorting is finished.
+    writes: usize,
+    swaps: usize,
 }
 
 impl Sorter for BubbleSort {
@@ -22,7 +23,8 @@ impl Sorter for BubbleSort {
             action_reason: Reasons::Comparing, // The action is "Comparing" initially.
             finished: false,                   // Sorting is not finished initially.
             comparisons: 0,
-            swaps: 0, // Sorting is not finished initially.
+            writes: 0,
+            swaps: 0,
         }
     }
 
@@ -33,19 +35,19 @@ impl Sorter for BubbleSort {
     /// * `true` if sorting is complete.
     /// * `false` if sorting is still in progress.
     fn step(&mut self, array: &mut Vec<usize>) -> bool {
-        play_beep();
-        self.swaps += 1;
         let len = array.len();
 
         // Check if the sorting is complete.
-        if self.pass == len - 1 {
+        if self.finished || self.pass >= len.saturating_sub(1) {
             self.finished = true; // Mark the sorting as finished.
+            self.index = None; // No elements are compared anymore.
             return true; // Sorting is complete.
         }
+        play_beep();
 
         // Determine the current index or initialize it if it's the first time.
         if let Some(idx) = self.index {
-            if idx < len - self.pass - 1 {
+            if idx + 1 < len - self.pass - 1 {
                 self.index = Some(idx + 1); // Move to the next pair of elements.
             } else {
                 self.pass += 1; // Move to the next pass (next iteration).
@@ -71,6 +73,7 @@ impl Sorter for BubbleSort {
                     play_beep();
                     self.swaps += 1;
                     array.swap(idx, idx + 1); // Swap the elements at `idx` and `idx + 1`.
+                    self.writes += 2;
 
                     self.needs_switch = false; // Reset the flag after the swap.
                 }
@@ -112,4 +115,8 @@ impl Sorter for BubbleSort {
     fn swaps(&self) -> usize {
         self.swaps
     }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
 }
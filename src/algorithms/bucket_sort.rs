@@ -0,0 +1,304 @@
+use super::insertion_sort::InsertionSort;
+use super::structure::Structure;
+use super::subrange::RangeSorter;
+use super::{Reasons, Sorter};
+use crate::rng;
+use crate::sound::play_beep;
+use rand::seq::index::sample;
+use std::borrow::Cow;
+
+/// Number of sampled elements per bucket used by sample sort to choose its splitters.
+const OVERSAMPLING: usize = 4;
+
+/// How the value boundaries between buckets are chosen.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Splitters {
+    /// Classic bucket sort: the value range is divided into buckets of equal width.
+    EqualWidth,
+    /// Sample sort: boundaries are taken at regular ranks of a sorted random sample,
+    /// so buckets stay balanced even on skewed inputs.
+    Sampled,
+}
+
+/// The stage of the algorithm currently running.
+#[derive(PartialEq, Clone, Copy)]
+enum Stage {
+    /// Choosing the bucket boundaries.
+    Setup,
+    /// Moving each element into its bucket.
+    Scatter,
+    /// Writing the buckets back to the array, one after another.
+    Gather,
+    /// Sorting the region of each bucket with the sub-sorter.
+    SortBuckets,
+}
+
+/// Represents BucketSort (and its sample sort variant) and its state.
+/// Elements are scattered into buckets by value, written back bucket by bucket,
+/// and each bucket's region is then sorted with a user-selected sub-sorter.
+pub struct BucketSort {
+    splitters: Splitters,     // How bucket boundaries are chosen.
+    bucket_count: usize,      // Number of buckets requested.
+    sub_sorter: RangeSorter,  // Sorts each bucket, reset between buckets.
+    stage: Stage,             // The stage currently running.
+    buckets: Vec<Vec<usize>>, // Values scattered into each bucket.
+    lower_bounds: Vec<usize>, // Smallest value accepted by each bucket.
+    bucket: usize,            // Bucket being gathered or sorted.
+    cursor: usize,            // Next element to scatter or to gather from the bucket.
+    index: usize,             // Next array index to gather into, or start of the bucket.
+    special: (usize, usize),  // Indices touched by the last step.
+    reason: Reasons,          // Reason for the current operation.
+    finished: bool,           // Indicates whether the sorting is complete.
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+}
+
+impl BucketSort {
+    /// Creates a bucket sort with the given boundary strategy, bucket count and sub-sorter.
+    pub fn with_options(
+        splitters: Splitters,
+        bucket_count: usize,
+        sub_sorter: Box<dyn Sorter>,
+    ) -> Self {
+        Self::with_range_sorter(splitters, bucket_count, RangeSorter::new(sub_sorter))
+    }
+
+    /// Creates a bucket sort around an already wrapped sub-sorter.
+    fn with_range_sorter(
+        splitters: Splitters,
+        bucket_count: usize,
+        sub_sorter: RangeSorter,
+    ) -> Self {
+        BucketSort {
+            splitters,
+            bucket_count: bucket_count.max(1),
+            sub_sorter,
+            stage: Stage::Setup,
+            buckets: Vec::new(),
+            lower_bounds: Vec::new(),
+            bucket: 0,
+            cursor: 0,
+            index: 0,
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+
+    /// Chooses the smallest value accepted by each bucket.
+    fn choose_bounds(&mut self, array: &[usize]) {
+        self.lower_bounds = match self.splitters {
+            Splitters::EqualWidth => {
+                let min = *array.iter().min().unwrap_or(&0);
+                let max = *array.iter().max().unwrap_or(&0);
+                self.comparisons += 2 * array.len().saturating_sub(1);
+                let width = (max - min) / self.bucket_count + 1;
+                (0..self.bucket_count).map(|i| min + i * width).collect()
+            }
+            Splitters::Sampled => {
+                let size = (OVERSAMPLING * self.bucket_count).min(array.len());
+                let mut samples: Vec<usize> = rng::with_rng(|rng| sample(rng, array.len(), size))
+                    .into_iter()
+                    .map(|i| array[i])
+                    .collect();
+                let comparisons = &mut self.comparisons;
+                samples.sort_by(|a, b| {
+                    *comparisons += 1;
+                    a.cmp(b)
+                });
+                // Bucket 0 takes everything below the first splitter.
+                let mut bounds = vec![0];
+                bounds
+                    .extend((1..self.bucket_count).map(|i| samples[i * size / self.bucket_count]));
+                bounds.dedup();
+                bounds
+            }
+        };
+        self.buckets = vec![Vec::new(); self.lower_bounds.len()];
+    }
+
+    /// Finds the bucket for `value` with a binary search over the lower bounds.
+    fn bucket_of(&mut self, value: usize) -> usize {
+        let (mut low, mut high) = (0, self.lower_bounds.len());
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+            self.comparisons += 1;
+            if self.lower_bounds[middle] <= value {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        low
+    }
+
+    /// Runs one step of the sub-sorter on the current bucket's region.
+    /// # Returns
+    /// `true` once the bucket is sorted.
+    fn sort_bucket_step(&mut self, array: &mut [usize]) -> bool {
+        if !self.sub_sorter.is_running() {
+            let len = self.buckets[self.bucket].len();
+            self.sub_sorter.start(array, self.index, self.index + len);
+        }
+        let done = self.sub_sorter.step(
+            array,
+            &mut self.comparisons,
+            &mut self.swaps,
+            &mut self.writes,
+        );
+        self.special = self.sub_sorter.focus();
+        self.reason = self.sub_sorter.reason();
+        done
+    }
+}
+
+impl Sorter for BucketSort {
+    /// Creates a bucket sort with 8 equal-width buckets, each sorted with InsertionSort.
+    fn new() -> Self {
+        Self::with_options(Splitters::EqualWidth, 8, Box::new(InsertionSort::new()))
+    }
+
+    /// Returns the indices touched by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step: scatters one element, gathers one element,
+    /// or advances the sub-sorter of the current bucket by one step.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+
+        loop {
+            match self.stage {
+                Stage::Setup => {
+                    if array.len() < 2 {
+                        self.finished = true;
+                        return true;
+                    }
+                    self.choose_bounds(array);
+                    self.cursor = 0;
+                    self.stage = Stage::Scatter;
+                    return false;
+                }
+                Stage::Scatter => {
+                    if self.cursor == array.len() {
+                        (self.bucket, self.cursor, self.index) = (0, 0, 0);
+                        self.stage = Stage::Gather;
+                        continue;
+                    }
+                    let value = array[self.cursor];
+                    let bucket = self.bucket_of(value);
+                    self.buckets[bucket].push(value);
+                    self.special = (self.cursor, self.cursor);
+                    self.reason = Reasons::Comparing;
+                    self.cursor += 1;
+                    return false;
+                }
+                Stage::Gather => {
+                    if self.bucket == self.buckets.len() {
+                        (self.bucket, self.index) = (0, 0);
+                        self.stage = Stage::SortBuckets;
+                        continue;
+                    }
+                    if self.cursor == self.buckets[self.bucket].len() {
+                        self.bucket += 1;
+                        self.cursor = 0;
+                        continue;
+                    }
+                    array[self.index] = self.buckets[self.bucket][self.cursor];
+                    self.writes += 1;
+                    self.special = (self.index, self.index);
+                    self.reason = Reasons::Switching;
+                    play_beep();
+                    self.cursor += 1;
+                    self.index += 1;
+                    return false;
+                }
+                Stage::SortBuckets => {
+                    if self.bucket == self.buckets.len() {
+                        self.finished = true;
+                        self.special = (usize::MAX, usize::MAX);
+                        return true;
+                    }
+                    // Empty and single-element buckets are already sorted.
+                    let len = self.buckets[self.bucket].len();
+                    if len < 2 || self.sort_bucket_step(array) {
+                        self.index += len;
+                        self.bucket += 1;
+                    }
+                    if len >= 2 {
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Resets the state for a fresh sort, keeping the options and sub-sorter.
+    fn reset_state(&mut self) {
+        let placeholder = RangeSorter::new(Box::new(InsertionSort::new()));
+        let mut sub_sorter = std::mem::replace(&mut self.sub_sorter, placeholder);
+        sub_sorter.reset();
+        *self = Self::with_range_sorter(self.splitters, self.bucket_count, sub_sorter);
+    }
+
+    /// Checks if the sorting process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    fn auxiliary_memory(&self) -> usize {
+        self.buckets.iter().map(Vec::len).sum::<usize>() + self.sub_sorter.auxiliary_memory()
+    }
+
+    /// Returns the stage currently running.
+    fn phase(&self) -> Option<&'static str> {
+        match self.stage {
+            Stage::Setup if self.splitters == Splitters::Sampled => Some("Sampling splitters"),
+            Stage::Setup => Some("Finding the value range"),
+            Stage::Scatter => Some("Scattering into buckets"),
+            Stage::Gather => Some("Gathering buckets"),
+            Stage::SortBuckets if self.finished => None,
+            Stage::SortBuckets => Some("Sorting each bucket"),
+        }
+    }
+
+    /// Returns the buckets and their boundaries.
+    fn structure(&self) -> Option<Structure<'_>> {
+        if self.buckets.is_empty() {
+            return None;
+        }
+        let current = match self.stage {
+            Stage::Gather | Stage::SortBuckets if !self.finished => Some(self.bucket),
+            _ => None,
+        };
+        Some(Structure::Buckets {
+            buckets: Cow::Borrowed(&self.buckets),
+            lower_bounds: Cow::Borrowed(&self.lower_bounds),
+            current,
+        })
+    }
+}
@@ -10,6 +10,7 @@ pub struct CocktailSort {
     finished: bool,
     current: usize,
     comparisons: usize,
+    writes: usize,
     swaps: usize,
 }
 
@@ -23,6 +24,7 @@ impl CocktailSort {
             finished: false,
             current: 0,
             comparisons: 0,
+            writes: 0,
             swaps: 0,
         }
     }
@@ -41,6 +43,12 @@ impl CocktailSort {
     }
 }
 
+impl Default for CocktailSort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Sorter for CocktailSort {
     fn new() -> Self {
         Self::new()
@@ -65,6 +73,11 @@ impl Sorter for CocktailSort {
     }
 
     fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        // Once finished, `end` may be back at 0, which must not start a new sort.
+        if self.finished {
+            return true;
+        }
+
         // Early return for empty or single-element arrays
         if array.is_empty() || array.len() == 1 {
             self.finished = true;
@@ -93,6 +106,7 @@ impl Sorter for CocktailSort {
                     array.swap(self.current, self.current + 1);
                     play_beep();
                     self.swaps += 1;
+                    self.writes += 2;
                     self.swapped = true;
                 }
 
@@ -112,6 +126,7 @@ impl Sorter for CocktailSort {
                     array.swap(self.current - 1, self.current);
                     play_beep();
                     self.swaps += 1;
+                    self.writes += 2;
                     self.swapped = true;
                 }
 
@@ -158,4 +173,8 @@ impl Sorter for CocktailSort {
     fn swaps(&self) -> usize {
         self.swaps
     }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
 }
@@ -1,29 +1,44 @@
 use super::{Reasons, Sorter};
 use crate::sound::play_beep;
 
+/// The shrink factor recommended by Lacey and Box.
+pub const DEFAULT_SHRINK: f64 = 1.3;
+
 pub struct CombSort {
+    shrink: f64, // Factor the gap is divided by after each pass.
     gap: usize,
     swapped: bool,
     i: usize,
+    special: (usize, usize), // The pair compared by the last step.
     finished: bool,
     swaps: usize, // Indicates if the sorting is finished.
     comparisons: usize,
+    writes: usize,
 }
 
 impl CombSort {
     pub fn new() -> Self {
+        Self::with_shrink(DEFAULT_SHRINK)
+    }
+
+    /// Creates a CombSort that divides its gap by `shrink` after each pass.
+    pub fn with_shrink(shrink: f64) -> Self {
         Self {
+            shrink: shrink.max(1.01),
             gap: 0,
             swapped: true,
             i: 0,
+            special: (usize::MAX, usize::MAX),
             finished: false, // Sorting is not finished initially.
             comparisons: 0,
+            writes: 0,
             swaps: 0,
         }
     }
 
-    fn get_next_gap(gap: usize) -> usize {
-        let new_gap = (gap * 10) / 13;
+    fn get_next_gap(&self, gap: usize) -> usize {
+        // The small epsilon keeps exact quotients such as 13 / 1.3 from rounding down.
+        let new_gap = (gap as f64 / self.shrink + 1e-9) as usize;
         if new_gap < 1 {
             1
         } else {
@@ -32,6 +47,12 @@ impl CombSort {
     }
 }
 
+impl Default for CombSort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Sorter for CombSort {
     fn new() -> Self {
         Self::new()
@@ -41,7 +62,7 @@ impl Sorter for CombSort {
         if self.finished {
             (usize::MAX, usize::MAX)
         } else {
-            (self.i, self.i + self.gap.min(1)) // Highlights compared elements
+            self.special // Highlights compared elements
         }
     }
 
@@ -60,36 +81,39 @@ impl Sorter for CombSort {
 
         // Initialize gap on first run
         if self.gap == 0 {
-            self.gap = array.len();
-            self.i = 0;
-        }
-
-        if self.gap != 1 || self.swapped {
-            self.gap = Self::get_next_gap(self.gap);
+            self.gap = self.get_next_gap(array.len());
             self.swapped = false;
             self.i = 0;
         }
 
         if self.i + self.gap < array.len() {
             self.comparisons += 1;
+            self.special = (self.i, self.i + self.gap);
 
             if array[self.i] > array[self.i + self.gap] {
                 play_beep();
                 self.swaps += 1;
                 array.swap(self.i, self.i + self.gap);
+                self.writes += 2;
                 self.swapped = true;
             }
 
             self.i += 1;
         } else if self.gap == 1 && !self.swapped {
             self.finished = true;
+            return true;
+        } else {
+            // Shrink the gap after each full pass
+            self.gap = self.get_next_gap(self.gap);
+            self.swapped = false;
+            self.i = 0;
         }
 
         false
     }
 
     fn reset_state(&mut self) {
-        *self = Self::new(); // Reset all fields to their initial state.
+        *self = Self::with_shrink(self.shrink); // Keep the shrink factor.
     }
 
     fn is_finished(&self) -> bool {
@@ -102,4 +126,8 @@ impl Sorter for CombSort {
     fn swaps(&self) -> usize {
         self.swaps
     }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
 }
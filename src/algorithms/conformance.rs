@@ -0,0 +1,284 @@
+//! Conformance tests every `Sorter` must pass, run on a set of edge-case and random inputs.
+
+use super::{input_limit, intro_sort::IntroSort, Sorter};
+use crate::params::{self, ParamValue, Settings};
+use crate::shuffle::{Shuffle, ShuffleMethod};
+use crate::sound;
+use crate::types::Algorithms;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use strum::IntoEnumIterator;
+
+/// Steps after which a run is considered stuck.
+const MAX_STEPS: usize = 2_000_000;
+
+/// Algorithms whose steps depend on random choices with their default parameters.
+const RANDOMIZED: [Algorithms; 2] = [Algorithms::Bogo, Algorithms::Sample];
+
+/// Returns an organ pipe of `len` elements, rising then falling. Median-of-three pivots
+/// keep splitting off only a few elements of it, which drives IntroSort into its
+/// depth limit.
+fn organ_pipe(len: usize) -> Vec<usize> {
+    (0..len / 2).chain((0..len - len / 2).rev()).collect()
+}
+
+/// The inputs every sorter is run on, with a name for failure messages.
+/// The longer ones reach the paths algorithms like PdqSort only take on 50 or more elements.
+fn inputs() -> Vec<(&'static str, Vec<usize>)> {
+    let mut rng = StdRng::seed_from_u64(41);
+    vec![
+        ("empty", vec![]),
+        ("single", vec![5]),
+        ("pair", vec![2, 1]),
+        ("all equal", vec![7; 12]),
+        ("sorted", (1..=12).collect()),
+        ("reversed", (1..=12).rev().collect()),
+        ("random", (0..24).map(|_| rng.gen_range(1..=10)).collect()),
+        (
+            "random 64",
+            (0..64).map(|_| rng.gen_range(1..=100)).collect(),
+        ),
+        (
+            "random 256",
+            (0..256).map(|_| rng.gen_range(1..=1000)).collect(),
+        ),
+        (
+            "few unique 256",
+            (0..256).map(|_| rng.gen_range(0..4)).collect(),
+        ),
+        ("organ pipe 256", organ_pipe(256)),
+    ]
+}
+
+/// Creates the Bucket, Sample and Hybrid sorters with every choice of sub-sort,
+/// with a name for failure messages.
+fn sub_sort_sorters() -> Vec<(Algorithms, String, Box<dyn Sorter>)> {
+    let mut sorters = Vec::new();
+    for (algorithm, slot) in [
+        (Algorithms::Bucket, 1),
+        (Algorithms::Sample, 1),
+        (Algorithms::Hybrid, 2),
+    ] {
+        for (choice, sub_sort) in params::sub_sorts().into_iter().enumerate() {
+            let mut settings = Settings::default();
+            let mut values = settings.values(algorithm);
+            values[slot] = ParamValue::Choice(choice);
+            settings.set_values(algorithm, values);
+            let name = format!("{} with {}", algorithm.label(), sub_sort.label());
+            sorters.push((algorithm, name, settings.create_sorter(algorithm)));
+        }
+    }
+    sorters
+}
+
+/// Creates the sorter of every algorithm with its default parameters.
+pub(super) fn sorters() -> Vec<(Algorithms, Box<dyn Sorter>)> {
+    let settings = Settings::default();
+    Algorithms::iter()
+        .map(|algorithm| (algorithm, settings.create_sorter(algorithm)))
+        .collect()
+}
+
+/// The counters of a sorter, compared as a whole.
+pub(super) fn counters(sorter: &dyn Sorter) -> (usize, usize, usize) {
+    (sorter.comparisons(), sorter.swaps(), sorter.writes())
+}
+
+/// Steps `sorter` through `array` to completion, checking the step protocol on the way:
+/// `step` returns `true` exactly once, `is_finished` agrees with it, `special()` stays in
+/// bounds, and once finished further steps change nothing.
+pub(super) fn run(sorter: &mut dyn Sorter, array: &mut Vec<usize>, context: &str) {
+    let len = array.len();
+    assert!(
+        !sorter.is_finished(),
+        "{context}: finished before the first step"
+    );
+    let mut steps = 0;
+    sound::muted(|| loop {
+        steps += 1;
+        assert!(
+            steps <= MAX_STEPS,
+            "{context}: no result after {MAX_STEPS} steps"
+        );
+        let finished = sorter.step(array);
+        assert_eq!(
+            array.len(),
+            len,
+            "{context}: step {steps} resized the array"
+        );
+        let (a, b) = sorter.special();
+        assert!(
+            [a, b].iter().all(|&i| i < len || i == usize::MAX),
+            "{context}: special() is {:?} at step {steps}, out of bounds for {len} elements",
+            (a, b)
+        );
+        assert_eq!(
+            sorter.is_finished(),
+            finished,
+            "{context}: is_finished() disagrees with step() at step {steps}"
+        );
+        if finished {
+            break;
+        }
+    });
+
+    let (settled, totals) = (array.clone(), counters(sorter));
+    sound::muted(|| {
+        for _ in 0..3 {
+            assert!(sorter.step(array), "{context}: step() unfinished again");
+        }
+    });
+    assert!(sorter.is_finished(), "{context}: no longer finished");
+    assert_eq!(
+        *array, settled,
+        "{context}: finished sorter changed the array"
+    );
+    assert_eq!(
+        counters(sorter),
+        totals,
+        "{context}: finished sorter counted more"
+    );
+}
+
+/// Returns `array` sorted, the expected result of a full sort.
+fn sorted(array: &[usize]) -> Vec<usize> {
+    let mut sorted = array.to_vec();
+    sorted.sort_unstable();
+    sorted
+}
+
+/// Checks that `array` is the sorted permutation of `input` a finished `sorter` must leave,
+/// or for partial algorithms a permutation whose target positions hold their sorted values.
+pub(super) fn check_result(
+    algorithm: Algorithms,
+    sorter: &dyn Sorter,
+    input: &[usize],
+    array: &[usize],
+    context: &str,
+) {
+    let expected = sorted(input);
+    assert_eq!(sorted(array), expected, "{context}: not a permutation");
+    if algorithm.is_partial() {
+        // Only the target positions are final; the rest just has to be a permutation.
+        let (low, high) = sorter.target().unwrap_or((0, 0));
+        assert_eq!(
+            array[low..high],
+            expected[low..high],
+            "{context}: wrong target"
+        );
+    } else {
+        assert_eq!(array, expected, "{context}: not sorted");
+    }
+}
+
+#[test]
+fn sorters_produce_a_sorted_permutation() {
+    for (algorithm, mut sorter) in sorters() {
+        for (name, input) in inputs() {
+            let input = &input[..input_limit(sorter.as_ref(), input.len())];
+            let context = format!("{} on {name} input", algorithm.label());
+            let mut array = input.to_vec();
+            run(sorter.as_mut(), &mut array, &context);
+
+            check_result(algorithm, sorter.as_ref(), input, &array, &context);
+            sorter.reset_state();
+        }
+    }
+}
+
+#[test]
+fn every_sub_sort_choice_finishes() {
+    for (algorithm, label, mut sorter) in sub_sort_sorters() {
+        for (name, input) in inputs() {
+            let input = &input[..input_limit(sorter.as_ref(), input.len())];
+            let context = format!("{label} on {name} input");
+            let mut array = input.to_vec();
+            run(sorter.as_mut(), &mut array, &context);
+
+            check_result(algorithm, sorter.as_ref(), input, &array, &context);
+            sorter.reset_state();
+        }
+    }
+}
+
+#[test]
+fn organ_pipe_reaches_the_intro_sort_fallback() {
+    let mut sorter = IntroSort::new();
+    let mut array = organ_pipe(256);
+    let mut fell_back = false;
+    sound::muted(|| {
+        while !sorter.step(&mut array) {
+            fell_back |= sorter.phase() == Some("Heapsort fallback (depth limit reached)");
+        }
+    });
+    assert!(fell_back, "IntroSort never reached its depth limit");
+    assert_eq!(array, sorted(&organ_pipe(256)));
+}
+
+#[test]
+fn slow_algorithms_declare_a_step_estimate() {
+    for (algorithm, sorter) in sorters() {
+        assert_eq!(
+            sorter.estimated_steps(100).is_some(),
+            algorithm.is_slow(),
+            "{}: is_slow() disagrees with estimated_steps()",
+            algorithm.label()
+        );
+    }
+}
+
+#[test]
+fn reset_state_resets_everything() {
+    let settings = Settings::default();
+    for (algorithm, mut sorter) in sorters() {
+        for (name, input) in inputs() {
+            let input = &input[..input_limit(sorter.as_ref(), input.len())];
+            let context = format!("{} on {name} input", algorithm.label());
+            run(sorter.as_mut(), &mut input.to_vec(), &context);
+            sorter.reset_state();
+            assert_eq!(
+                counters(sorter.as_ref()),
+                (0, 0, 0),
+                "{context}: counters kept"
+            );
+            assert!(
+                !sorter.is_finished(),
+                "{context}: still finished after reset"
+            );
+
+            // A reset sorter must behave exactly like a new one, unless it makes random choices.
+            let mut array = input.to_vec();
+            run(sorter.as_mut(), &mut array, &context);
+            if !RANDOMIZED.contains(&algorithm) {
+                let mut fresh = settings.create_sorter(algorithm);
+                let mut fresh_array = input.to_vec();
+                run(fresh.as_mut(), &mut fresh_array, &context);
+                assert_eq!(
+                    (array, counters(sorter.as_ref())),
+                    (fresh_array, counters(fresh.as_ref())),
+                    "{context}: reset sorter differs from a new one"
+                );
+            }
+            sorter.reset_state();
+        }
+    }
+}
+
+#[test]
+fn shuffles_produce_a_permutation() {
+    for method in ShuffleMethod::iter() {
+        let mut shuffle = Shuffle::with_method(method);
+        for (name, input) in inputs() {
+            let context = format!("{} shuffle on {name} input", method.label());
+            let mut array = input.clone();
+            run(&mut shuffle, &mut array, &context);
+            assert_eq!(
+                sorted(&array),
+                sorted(&input),
+                "{context}: not a permutation"
+            );
+            shuffle.reset_state();
+            assert_eq!(counters(&shuffle), (0, 0, 0), "{context}: counters kept");
+        }
+    }
+}
@@ -14,6 +14,7 @@ pub struct CountingSort {
     processing_index: usize, // Tracks which element is being processed.
     swaps: usize,            // Indicates if the sorting is finished.
     comparisons: usize,
+    writes: usize,
 }
 
 impl Sorter for CountingSort {
@@ -30,6 +31,7 @@ impl Sorter for CountingSort {
             array_index: 0,
             processing_index: 0,
             comparisons: 0,
+            writes: 0,
             swaps: 0,
         }
     }
@@ -68,6 +70,7 @@ impl Sorter for CountingSort {
                 if self.current_value <= self.max_value {
                     if self.counts[self.current_value] > 0 {
                         array[self.array_index] = self.current_value;
+                        self.writes += 1;
                         self.counts[self.current_value] -= 1;
                         self.reason = Reasons::Switching;
                         play_beep();
@@ -85,6 +88,7 @@ impl Sorter for CountingSort {
             3 => {
                 // Step 4: Sorting complete
                 self.is_sorted = true;
+                return true;
             }
             _ => {
                 return true;
@@ -108,13 +112,22 @@ impl Sorter for CountingSort {
         self.swaps
     }
 
-    /// Returns the special indices currently being processed.
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    fn auxiliary_memory(&self) -> usize {
+        self.counts.len() + self.output.len()
+    }
+
+    /// Returns the index handled by the last step: the element just counted or just written.
     fn special(&self) -> (usize, usize) {
-        match self.step_phase {
-            1 => (self.processing_index, self.processing_index), // Counting phase
-            2 => (self.array_index, self.array_index),           // Placing phase
-            _ => (usize::MAX, usize::MAX),
-        }
+        let last = match self.step_phase {
+            1 => self.processing_index.checked_sub(1), // Counting phase
+            2 => self.array_index.checked_sub(1),      // Placing phase
+            _ => None,
+        };
+        last.map_or((usize::MAX, usize::MAX), |index| (index, index))
     }
 
     /// Returns the reason for the current sorting action.
@@ -0,0 +1,132 @@
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+
+/// Represents the CycleSort algorithm and its state.
+/// CycleSort writes every element directly to its final position, so it performs
+/// the minimum possible number of memory writes. Each step places one element.
+pub struct CycleSort {
+    cycle_start: usize,  // Start of the cycle currently being rotated.
+    item: Option<usize>, // Value held "in hand" while its cycle is rotated.
+    position: usize,     // Position the last item was written to.
+    reason: Reasons,     // Reason for the current operation.
+    finished: bool,      // Indicates whether the sorting is complete.
+    comparisons: usize,
+    writes: usize,
+    swaps: usize,
+}
+
+impl CycleSort {
+    /// Finds the final position of `item` by counting the smaller elements after `cycle_start`,
+    /// then skips past any duplicates already placed there.
+    fn find_position(&mut self, array: &[usize], item: usize) -> usize {
+        let mut position = self.cycle_start;
+        for &value in &array[self.cycle_start + 1..] {
+            self.comparisons += 1;
+            if value < item {
+                position += 1;
+            }
+        }
+        while position != self.cycle_start && array[position] == item {
+            self.comparisons += 1;
+            position += 1;
+        }
+        position
+    }
+}
+
+impl Sorter for CycleSort {
+    /// Creates a new instance of CycleSort.
+    fn new() -> Self {
+        CycleSort {
+            cycle_start: 0,
+            item: None,
+            position: usize::MAX,
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            writes: 0,
+            swaps: 0,
+        }
+    }
+
+    /// Returns the start of the current cycle and the position written last.
+    fn special(&self) -> (usize, usize) {
+        if self.finished {
+            (usize::MAX, usize::MAX)
+        } else {
+            (self.cycle_start, self.position)
+        }
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step of CycleSort, writing one element to its final position.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+
+        if self.cycle_start + 1 >= array.len() {
+            self.finished = true;
+            return true;
+        }
+
+        // Pick up the element at the start of a new cycle.
+        let item = match self.item {
+            Some(item) => item,
+            None => array[self.cycle_start],
+        };
+
+        let position = self.find_position(array, item);
+        self.position = position;
+
+        if position == self.cycle_start && self.item.is_none() {
+            // The element is already in place; move on to the next cycle.
+            self.reason = Reasons::Comparing;
+            self.cycle_start += 1;
+            return false;
+        }
+
+        // Write the held item and pick up the element it displaces.
+        let displaced = std::mem::replace(&mut array[position], item);
+        self.writes += 1;
+        self.swaps += 1;
+        self.reason = Reasons::Switching;
+        play_beep();
+
+        if position == self.cycle_start {
+            // The cycle is closed.
+            self.item = None;
+            self.cycle_start += 1;
+        } else {
+            self.item = Some(displaced);
+        }
+
+        false
+    }
+
+    /// Resets the state of the CycleSort instance for a fresh sort.
+    fn reset_state(&mut self) {
+        *self = Self::new(); // Reset all fields to their initial state.
+    }
+
+    /// Checks if the sorting process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+}
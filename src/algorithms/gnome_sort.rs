@@ -6,7 +6,8 @@ pub struct GnomeSort {
     index: usize, // Current index in the array
     finished: bool,
     comparisons: usize, // counts the number of comparisons
-    swaps: usize,       // Indicates whether sorting is complete
+    writes: usize,
+    swaps: usize, // Indicates whether sorting is complete
 }
 
 impl GnomeSort {
@@ -16,11 +17,18 @@ impl GnomeSort {
             index: 1,        // Starts at the second element, like in the C++ version
             finished: false, // Sorting is not finished initially.
             comparisons: 0,
+            writes: 0,
             swaps: 0,
         }
     }
 }
 
+impl Default for GnomeSort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Sorter for GnomeSort {
     /// Initializes a new sorter instance.
     fn new() -> Self {
@@ -67,9 +75,15 @@ impl Sorter for GnomeSort {
             array.swap(self.index, self.index - 1); // Swap elements
             self.index -= 1; // Move backward
             self.swaps += 1;
+            self.writes += 2;
             play_beep();
         }
 
+        // Stepping past the last element means every element is in place.
+        if self.index >= array.len() {
+            self.finished = true;
+            return true;
+        }
         false
     }
 
@@ -89,4 +103,8 @@ impl Sorter for GnomeSort {
     fn swaps(&self) -> usize {
         self.swaps
     }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
 }
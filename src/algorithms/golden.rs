@@ -0,0 +1,143 @@
+//! Golden trace tests: every algorithm sorts a fixed input with a fixed seed, and the
+//! `special()` pair, `reason()` and counters after each step must match the trace recorded
+//! in `tests/golden`. A change to an algorithm's visual behavior or counts shows up as a diff.
+//!
+//! After an intended change, rerecord the traces with `UPDATE_GOLDEN=1 cargo test golden`.
+
+use super::intro_sort::IntroSort;
+use super::{input_limit_within, Sorter};
+use crate::params::Settings;
+use crate::rng;
+use crate::shuffle::{Shuffle, ShuffleMethod};
+use crate::sound;
+use crate::types::Algorithms;
+use rand::Rng;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use strum::IntoEnumIterator;
+
+/// Seed of the input and of every random choice the algorithms make.
+const SEED: u64 = 43;
+
+/// Length of the traced input.
+const LEN: usize = 12;
+
+/// Steps a traced run may be expected to take, so slow algorithms get a shorter input
+/// and their traces stay readable.
+const TRACE_BUDGET: f64 = 1_000.0;
+
+/// Returns the traced input, with duplicates so stability-related branches are covered.
+fn input() -> Vec<usize> {
+    rng::seeded(SEED, || {
+        rng::with_rng(|rng| (0..LEN).map(|_| rng.gen_range(1..=9)).collect())
+    })
+}
+
+/// Formats an index of `special()`, with `-` for none.
+fn index(i: usize) -> String {
+    if i == usize::MAX {
+        "-".to_string()
+    } else {
+        i.to_string()
+    }
+}
+
+/// Runs `sorter` on `input` with the fixed seed and records one line per step.
+fn trace(name: &str, sorter: &mut dyn Sorter, input: &[usize]) -> String {
+    let mut array = input.to_vec();
+    let mut trace = format!("# {name}\n# seed {SEED}\n# input {:?}\n", array);
+    trace.push_str("# step special reason comparisons swaps writes\n");
+    rng::seeded(SEED, || {
+        sound::muted(|| {
+            for step in 1.. {
+                let finished = sorter.step(&mut array);
+                let (a, b) = sorter.special();
+                writeln!(
+                    trace,
+                    "{step} {},{} {:?} {} {} {}",
+                    index(a),
+                    index(b),
+                    sorter.reason(),
+                    sorter.comparisons(),
+                    sorter.swaps(),
+                    sorter.writes()
+                )
+                .unwrap();
+                if finished {
+                    break;
+                }
+            }
+        })
+    });
+    writeln!(trace, "# result {:?}", array).unwrap();
+    trace
+}
+
+/// Compares `trace` with the golden file `name`, or rewrites the file if `UPDATE_GOLDEN` is set.
+/// # Returns
+/// A description of the first difference, or `None` if the trace matches.
+fn compare(name: &str, trace: &str) -> Option<String> {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", name]
+        .iter()
+        .collect();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, trace).unwrap();
+        return None;
+    }
+    let Ok(golden) = fs::read_to_string(&path) else {
+        return Some(format!(
+            "{name}: no golden trace, record it with UPDATE_GOLDEN=1"
+        ));
+    };
+    let mut expected = golden.lines();
+    for (number, line) in trace.lines().enumerate() {
+        match expected.next() {
+            Some(golden_line) if golden_line == line => {}
+            golden_line => {
+                return Some(format!(
+                    "{name}:{}: expected {:?}, got {:?}",
+                    number + 1,
+                    golden_line.unwrap_or("end of trace"),
+                    line
+                ))
+            }
+        }
+    }
+    expected
+        .next()
+        .map(|line| format!("{name}: trace ends early, expected {:?}", line))
+}
+
+#[test]
+fn traces_match_golden_files() {
+    let settings = Settings::default();
+    let input = input();
+    let mut differences = Vec::new();
+
+    for algorithm in Algorithms::iter() {
+        let mut sorter = settings.create_sorter(algorithm);
+        let len = input_limit_within(sorter.as_ref(), input.len(), TRACE_BUDGET);
+        let trace = trace(&algorithm.label(), sorter.as_mut(), &input[..len]);
+        let name = format!("{:?}.trace", algorithm).to_lowercase();
+        differences.extend(compare(&name, &trace));
+    }
+    // The input is below IntroSort's default insertion threshold, so trace its partitions
+    // with a smaller one.
+    let mut intro = IntroSort::with_threshold(4);
+    let partitions = trace("Intro Sort (threshold 4)", &mut intro, &input);
+    differences.extend(compare("intro_partition.trace", &partitions));
+    for method in ShuffleMethod::iter() {
+        let mut shuffle = Shuffle::with_method(method);
+        let trace = trace(method.label(), &mut shuffle, &input);
+        let name = format!("shuffle_{:?}.trace", method).to_lowercase();
+        differences.extend(compare(&name, &trace));
+    }
+
+    assert!(
+        differences.is_empty(),
+        "traces differ from the golden files:\n{}",
+        differences.join("\n")
+    );
+}
@@ -6,8 +6,10 @@ pub struct HeapSort {
     index: usize,    // Tracks the current position being sorted.
     root: usize,     // Tracks the root of the current subtree.
     reason: Reasons, // Reason for the current sorting action (Comparing or Switching).
+    finished: bool,  // Indicates if the sorting is finished.
     swaps: usize,    // Indicates if the sorting is finished.
     comparisons: usize,
+    writes: usize,
 }
 
 impl HeapSort {
@@ -26,7 +28,7 @@ impl HeapSort {
         }
         self.comparisons += 1;
         // Check if the right child exists and is larger than the left child.
-        if child + 1 <= end_index && array[child] < array[child + 1] {
+        if child < end_index && array[child] < array[child + 1] {
             child += 1; // Right child is larger, so we choose it.
         }
 
@@ -39,6 +41,7 @@ impl HeapSort {
             self.reason = Reasons::Switching; // Indicate that a swap occurred.
             play_beep();
             self.swaps += 1;
+            self.writes += 2;
             return false; // Continue sifting down.
         }
 
@@ -54,8 +57,10 @@ impl Sorter for HeapSort {
             index: usize::MAX,          // Initially, the index is not set.
             root: usize::MAX,           // Initially, there is no root node.
             reason: Reasons::Comparing, // Initially, we're comparing elements.
+            finished: false,            // Sorting is not finished initially.
             swaps: 0,                   // Indicates if the sorting is finished.
             comparisons: 0,
+            writes: 0,
         }
     }
 
@@ -78,6 +83,10 @@ impl Sorter for HeapSort {
         self.swaps
     }
 
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
     /// Performs one step of the HeapSort algorithm.
     /// # Arguments
     /// * `array` - A mutable reference to the array being sorted.
@@ -85,10 +94,18 @@ impl Sorter for HeapSort {
     /// * `true` if sorting is complete.
     /// * `false` if sorting is still in progress.
     fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+        // Fewer than two elements are already sorted.
+        if array.len() < 2 {
+            self.finished = true;
+            return true;
+        }
         if self.index == usize::MAX {
             // First step: Build the heap.
             self.index = array.len() - 1; // Start from the last element.
-            self.root = (self.index + 1) / 2 - 1; // The last non-leaf node.
+            self.root = self.index.div_ceil(2) - 1; // The last non-leaf node.
         }
 
         if self.root != usize::MAX {
@@ -104,12 +121,14 @@ impl Sorter for HeapSort {
         } else {
             // After building the heap, start the sorting process by extracting the root.
             if self.index == 0 {
+                self.finished = true;
                 return true; // Sorting is complete.
             }
 
             // Swap the root with the last unsorted element (this moves the largest element to the end).
             array.swap(0, self.index);
             self.swaps += 1;
+            self.writes += 2;
             play_beep();
             self.index -= 1; // Decrease the heap size.
             self.root = 0; // Start sifting down the new root.
@@ -126,6 +145,6 @@ impl Sorter for HeapSort {
     /// Checks if the sorting process is finished.
     /// # Returns `true` if sorting is finished, otherwise `false`.
     fn is_finished(&self) -> bool {
-        self.index == 0 // Sorting is finished when the index reaches 0.
+        self.finished
     }
 }
@@ -0,0 +1,182 @@
+use super::subrange::RangeHeap;
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+
+/// Share of the smallest elements the top-k algorithms sort, by default.
+pub const DEFAULT_K_PERCENT: usize = 10;
+
+/// Returns how many of `len` elements make up `percent`%, rounded up.
+pub(crate) fn percent_count(percent: usize, len: usize) -> usize {
+    (len * percent.min(100)).div_ceil(100)
+}
+
+/// The stage of the algorithm currently running.
+#[derive(PartialEq, Clone, Copy)]
+enum Stage {
+    /// Building a max-heap of the first k elements.
+    Build,
+    /// Replacing the heap maximum with every smaller element behind the heap.
+    Scan,
+    /// Heapsorting the k smallest elements in place.
+    Extract,
+}
+
+/// Represents heap-based top-k selection and its state.
+/// A max-heap holds the k smallest elements seen so far at the front of the array:
+/// every later element smaller than the heap maximum replaces it. The heap is then
+/// heapsorted, so the first k positions end up sorted while the rest stays unsorted.
+pub struct HeapTopK {
+    percent: usize,          // Share of the elements to sort, as a percentage.
+    k: usize,                // Number of smallest elements to sort, resolved at the first step.
+    heap: Option<RangeHeap>, // Heap over the first k positions.
+    stage: Stage,            // The stage currently running.
+    next: usize,             // Next element to compare with the heap maximum.
+    special: (usize, usize), // Indices touched by the last step.
+    reason: Reasons,         // Reason for the current operation.
+    finished: bool,          // Indicates whether the first k positions are final.
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+}
+
+impl HeapTopK {
+    /// Creates a top-k selection that sorts the smallest `percent`% of the elements.
+    pub fn with_percent(percent: usize) -> Self {
+        HeapTopK {
+            percent,
+            k: 0,
+            heap: None,
+            stage: Stage::Build,
+            next: 0,
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+}
+
+impl Sorter for HeapTopK {
+    /// Creates a top-k selection of the smallest 10% of the elements.
+    fn new() -> Self {
+        Self::with_percent(DEFAULT_K_PERCENT)
+    }
+
+    /// Returns the indices touched by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step: one level of a sift-down, one comparison with the heap
+    /// maximum, or one root extraction.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+        let heap = self.heap.get_or_insert_with(|| {
+            self.k = percent_count(self.percent, array.len());
+            self.next = self.k;
+            RangeHeap::new(0, self.k)
+        });
+        let swaps = self.swaps;
+
+        if self.stage == Stage::Build && heap.is_heap() {
+            self.stage = Stage::Scan;
+        }
+        if self.stage == Stage::Scan && heap.is_heap() {
+            if self.next == array.len() {
+                self.stage = Stage::Extract;
+            } else {
+                // A smaller element replaces the largest of the k kept so far.
+                self.comparisons += 1;
+                self.special = (0, self.next);
+                if self.k > 0 && array[self.next] < array[0] {
+                    array.swap(0, self.next);
+                    self.swaps += 1;
+                    self.writes += 2;
+                    play_beep();
+                    heap.sift_root();
+                }
+                self.next += 1;
+                self.reason = if self.swaps > swaps {
+                    Reasons::Switching
+                } else {
+                    Reasons::Comparing
+                };
+                return false;
+            }
+        }
+
+        let done = heap.step(
+            array,
+            &mut self.comparisons,
+            &mut self.swaps,
+            &mut self.writes,
+        );
+        self.special = heap.focus();
+        self.reason = if self.swaps > swaps {
+            Reasons::Switching
+        } else {
+            Reasons::Comparing
+        };
+        if done {
+            self.finished = true;
+            self.special = (usize::MAX, usize::MAX);
+            return true;
+        }
+        false
+    }
+
+    /// Resets the state for a fresh sort, keeping the share of elements to sort.
+    fn reset_state(&mut self) {
+        *self = Self::with_percent(self.percent);
+    }
+
+    /// Checks if the first k positions are final.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Returns the stage currently running.
+    fn phase(&self) -> Option<&'static str> {
+        match (self.finished, self.stage) {
+            (true, _) => None,
+            (false, Stage::Build) => Some("Building the heap"),
+            (false, Stage::Scan) => Some("Scanning for smaller elements"),
+            (false, Stage::Extract) => Some("Heapsorting the top k"),
+        }
+    }
+
+    /// Returns the first k positions.
+    fn target(&self) -> Option<(usize, usize)> {
+        self.heap.as_ref().map(|_| (0, self.k))
+    }
+
+    /// Returns the positions already extracted from the heap.
+    fn finalized(&self) -> Vec<usize> {
+        match &self.heap {
+            Some(_) if self.finished => (0..self.k).collect(),
+            Some(heap) if self.stage == Stage::Extract => (heap.sorted_from()..self.k).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
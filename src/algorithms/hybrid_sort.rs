@@ -0,0 +1,290 @@
+use super::insertion_sort::InsertionSort;
+use super::merge_sort::MergeSort;
+use super::quick_sort::{PartitionScheme, PivotStrategy, QuickSort};
+use super::subrange::RangeSorter;
+use super::{Reasons, Region, Sorter};
+use std::collections::VecDeque;
+use strum_macros::EnumIter;
+
+/// Length of the ranges handed to the inner algorithm, by default.
+pub const DEFAULT_CUTOFF: usize = 16;
+
+/// The algorithm that splits the array into short ranges for the inner algorithm.
+#[derive(PartialEq, Debug, EnumIter, Clone, Copy)]
+pub enum Outer {
+    /// QuickSort partitions until a range is shorter than the cutoff,
+    /// and the inner algorithm sorts that range right away.
+    QuickSort,
+    /// The inner algorithm sorts runs of the cutoff length, which MergeSort then merges.
+    MergeSort,
+}
+
+impl Outer {
+    /// Returns the name shown for the ranges this algorithm handles.
+    pub fn name(self) -> &'static str {
+        match self {
+            Outer::QuickSort => "Quick Sort",
+            Outer::MergeSort => "Merge Sort",
+        }
+    }
+}
+
+/// The running instance of the outer algorithm.
+enum OuterSorter {
+    Quick(QuickSort),
+    Merge(MergeSort),
+}
+
+/// Represents a hybrid of two existing sorters and its state.
+/// The outer algorithm works on the whole array, while every range shorter than the cutoff
+/// is handed off to the inner algorithm, as in "QuickSort until partitions are shorter
+/// than 16, then InsertionSort".
+pub struct HybridSort {
+    outer_kind: Outer,                 // Which algorithm splits the array.
+    cutoff: usize,                     // Length below which ranges are handed off.
+    outer: OuterSorter,                // The outer algorithm.
+    inner: RangeSorter,                // Sorts the handed-off ranges one at a time.
+    inner_name: String,                // Name shown for the handed-off ranges.
+    pending: VecDeque<(usize, usize)>, // Ranges `(low, end)` waiting for the inner algorithm.
+    handed_off: Vec<(usize, usize)>,   // Ranges given to the inner algorithm, the latest last.
+    inner_active: bool,                // Whether the last step was taken by the inner algorithm.
+    started: bool,                     // Whether the runs for MergeSort have been queued.
+    special: (usize, usize),           // Indices touched by the last step.
+    reason: Reasons,                   // Reason for the current operation.
+    finished: bool,                    // Indicates whether the sorting is complete.
+    comparisons: usize,                // Comparisons made by the inner algorithm.
+    swaps: usize,                      // Swaps made by the inner algorithm.
+    writes: usize,                     // Writes made by the inner algorithm.
+}
+
+impl HybridSort {
+    /// Creates a hybrid of `outer` and the `inner` sorter, named `inner_name` in the bar view.
+    /// # Arguments
+    /// * `outer` - The algorithm that splits the array.
+    /// * `cutoff` - QuickSort hands off partitions shorter than this; MergeSort starts from runs this long.
+    /// * `inner` - The sorter that finishes the short ranges.
+    /// * `inner_name` - The name shown for the ranges handed to `inner`.
+    pub fn with_options(
+        outer: Outer,
+        cutoff: usize,
+        inner: Box<dyn Sorter>,
+        inner_name: &str,
+    ) -> Self {
+        Self::with_range_sorter(outer, cutoff, RangeSorter::new(inner), inner_name)
+    }
+
+    /// Creates a hybrid around an already wrapped inner sorter.
+    fn with_range_sorter(
+        outer_kind: Outer,
+        cutoff: usize,
+        inner: RangeSorter,
+        inner_name: &str,
+    ) -> Self {
+        let cutoff = cutoff.max(2);
+        let outer = match outer_kind {
+            // Median-of-three Hoare partitioning, as in the classic Sedgewick hybrid.
+            Outer::QuickSort => OuterSorter::Quick(QuickSort::with_cutoff(
+                PivotStrategy::MedianOfThree,
+                PartitionScheme::Hoare,
+                cutoff,
+            )),
+            Outer::MergeSort => OuterSorter::Merge(MergeSort::with_run(cutoff)),
+        };
+        HybridSort {
+            outer_kind,
+            cutoff,
+            outer,
+            inner,
+            inner_name: inner_name.to_string(),
+            pending: VecDeque::new(),
+            handed_off: Vec::new(),
+            inner_active: false,
+            started: false,
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+
+    /// Returns the outer algorithm.
+    fn outer(&self) -> &dyn Sorter {
+        match &self.outer {
+            OuterSorter::Quick(quick) => quick,
+            OuterSorter::Merge(merge) => merge,
+        }
+    }
+
+    /// Returns the range the outer algorithm is working on, if any.
+    fn outer_range(&self) -> Option<(usize, usize)> {
+        match &self.outer {
+            OuterSorter::Quick(quick) => quick.range(),
+            OuterSorter::Merge(merge) => merge.range(),
+        }
+    }
+
+    /// Runs one step of the inner algorithm on the range handed off last.
+    fn inner_step(&mut self, array: &mut [usize]) {
+        self.inner.step(
+            array,
+            &mut self.comparisons,
+            &mut self.swaps,
+            &mut self.writes,
+        );
+        self.special = self.inner.focus();
+        self.reason = self.inner.reason();
+        self.inner_active = true;
+    }
+}
+
+impl Sorter for HybridSort {
+    /// Creates a hybrid that partitions with QuickSort and finishes ranges shorter
+    /// than 16 with InsertionSort.
+    fn new() -> Self {
+        Self::with_options(
+            Outer::QuickSort,
+            DEFAULT_CUTOFF,
+            Box::new(InsertionSort::new()),
+            "Insertion Sort",
+        )
+    }
+
+    /// Returns the indices touched by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step of the inner algorithm while a short range is waiting,
+    /// and a single step of the outer algorithm otherwise.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+
+        // MergeSort starts from runs that the inner algorithm has already sorted.
+        if !self.started {
+            self.started = true;
+            if self.outer_kind == Outer::MergeSort {
+                let n = array.len();
+                self.pending.extend(
+                    (0..n)
+                        .step_by(self.cutoff)
+                        .map(|low| (low, (low + self.cutoff).min(n)))
+                        .filter(|&(low, end)| end - low > 1),
+                );
+            }
+        }
+
+        if self.inner.is_running() {
+            self.inner_step(array);
+            return false;
+        }
+        if let Some((low, end)) = self.pending.pop_front() {
+            self.inner.start(array, low, end);
+            self.handed_off.push((low, end));
+            self.inner_step(array);
+            return false;
+        }
+
+        self.inner_active = false;
+        let done = match &mut self.outer {
+            OuterSorter::Quick(quick) => {
+                let done = quick.step(array);
+                self.pending.extend(quick.take_small_ranges());
+                done
+            }
+            OuterSorter::Merge(merge) => merge.step(array),
+        };
+        self.special = self.outer().special();
+        self.reason = self.outer().reason();
+
+        if done && self.pending.is_empty() {
+            self.finished = true;
+            self.special = (usize::MAX, usize::MAX);
+            return true;
+        }
+        false
+    }
+
+    /// Resets the state for a fresh sort, keeping the options and inner sorter.
+    fn reset_state(&mut self) {
+        let placeholder = RangeSorter::new(Box::new(InsertionSort::new()));
+        let mut inner = std::mem::replace(&mut self.inner, placeholder);
+        inner.reset();
+        *self = Self::with_range_sorter(self.outer_kind, self.cutoff, inner, &self.inner_name);
+    }
+
+    /// Checks if the sorting process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons + self.outer().comparisons()
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps + self.outer().swaps()
+    }
+
+    fn writes(&self) -> usize {
+        self.writes + self.outer().writes()
+    }
+
+    fn auxiliary_memory(&self) -> usize {
+        self.outer().auxiliary_memory() + self.inner.auxiliary_memory()
+    }
+
+    /// Returns which of the two algorithms took the last step.
+    fn phase(&self) -> Option<&'static str> {
+        match (self.finished, self.inner_active, self.outer_kind) {
+            (true, _, _) => None,
+            (false, true, _) => Some("Inner algorithm on a short range"),
+            (false, false, Outer::QuickSort) => Some("Partitioning"),
+            (false, false, Outer::MergeSort) => Some("Merging runs"),
+        }
+    }
+
+    /// Returns the pivots of the outer algorithm while it is running.
+    fn pivots(&self) -> Vec<usize> {
+        if self.inner_active || self.finished {
+            Vec::new()
+        } else {
+            self.outer().pivots()
+        }
+    }
+
+    /// Returns every range handed to the inner algorithm and the range of the outer one.
+    fn regions(&self) -> Vec<Region> {
+        let last = self.handed_off.len().saturating_sub(1);
+        let mut regions: Vec<Region> = self
+            .handed_off
+            .iter()
+            .enumerate()
+            .map(|(index, &(low, high))| Region {
+                low,
+                high,
+                algorithm: self.inner_name.clone(),
+                handed_off: true,
+                active: self.inner_active && index == last,
+            })
+            .collect();
+        if let Some((low, high)) = self.outer_range().filter(|_| !self.finished) {
+            regions.push(Region {
+                low,
+                high,
+                algorithm: self.outer_kind.name().to_string(),
+                handed_off: false,
+                active: !self.inner_active,
+            });
+        }
+        regions
+    }
+}
@@ -9,6 +9,7 @@ pub struct InsertionSort {
     is_sorted: bool,      // Indicates whether the sorting is complete.
     swaps: usize,         // Counts the number of swaps performed.
     comparisons: usize,   // Counts the number of comparisons performed.
+    writes: usize,
 }
 
 impl Sorter for InsertionSort {
@@ -20,12 +21,16 @@ impl Sorter for InsertionSort {
             reason: Reasons::Comparing, // Initial action is set to "Comparing".
             is_sorted: false,           // Initially, the sorting process is not complete.
             comparisons: 0,
+            writes: 0,
             swaps: 0,
         }
     }
 
     /// Returns the indices currently being compared or moved.
     fn special(&self) -> (usize, usize) {
+        if self.is_sorted {
+            return (usize::MAX, usize::MAX); // Nothing is compared once sorted.
+        }
         (self.current_index, self.sorted_index) // Return the indices of the current and sorted elements.
     }
 
@@ -54,6 +59,7 @@ impl Sorter for InsertionSort {
             self.comparisons += 1; // Increment comparisons
 
             array[i] = array[i - 1]; // Shift element to the right
+            self.writes += 1;
             i -= 1;
             self.reason = Reasons::Comparing;
         }
@@ -61,6 +67,7 @@ impl Sorter for InsertionSort {
         // If an actual swap occurred, update the array and play a beep
         if i != self.current_index {
             array[i] = value;
+            self.writes += 1;
             self.swaps += 1; // Increment swaps
             self.reason = Reasons::Switching;
             play_beep();
@@ -68,6 +75,11 @@ impl Sorter for InsertionSort {
 
         self.current_index += 1; // Move to the next element
 
+        // The last element has been inserted, so the sorting is complete.
+        if self.current_index >= array.len() {
+            self.is_sorted = true;
+            return true;
+        }
         false // Sorting is not complete yet.
     }
 
@@ -88,4 +100,8 @@ impl Sorter for InsertionSort {
     fn swaps(&self) -> usize {
         self.swaps
     }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
 }
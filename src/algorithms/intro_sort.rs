@@ -0,0 +1,259 @@
+use super::subrange::{RangeHeap, RangeInsertion};
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+
+/// Slices with at most this many elements are finished with insertion sort, by default.
+pub const DEFAULT_INSERTION_THRESHOLD: usize = 16;
+
+/// A Hoare partition of `low..high` in progress, advanced by one comparison per step.
+/// Both scans stop at elements equal to the pivot, so duplicates end up on both sides.
+struct Partition {
+    low: usize,
+    high: usize,         // One past the last index of the slice.
+    depth: usize,        // Depth budget left for the two sides.
+    i: usize,            // Left scan, stops at elements not less than the pivot.
+    j: usize,            // Right scan, stops at elements not greater than the pivot.
+    pivot: usize,        // Value of the median of three the slice is split around.
+    scanning_left: bool, // Whether the left scan is still moving.
+}
+
+/// Represents the IntroSort algorithm and its state.
+/// IntroSort runs QuickSort with a median-of-three pivot, switches to HeapSort
+/// once a slice exceeds its recursion depth budget, and finishes small slices
+/// with InsertionSort.
+pub struct IntroSort {
+    insertion_threshold: usize, // Largest slice finished with insertion sort.
+    stack: Vec<(usize, usize, usize)>, // Pending slices as (low, high exclusive, depth budget).
+    partition: Option<Partition>, // Active partition of a large slice.
+    insertion: Option<RangeInsertion>, // Active insertion sort on a small slice.
+    heap: Option<RangeHeap>,    // Active heapsort fallback on a deep slice.
+    special: (usize, usize),    // Indices highlighted by the last step.
+    phase: Option<&'static str>, // Strategy currently applied to the array.
+    reason: Reasons,            // Reason for the current operation.
+    started: bool,              // Whether the initial slice has been pushed.
+    finished: bool,             // Indicates whether the sorting is complete.
+    swaps: usize,
+    comparisons: usize,
+    writes: usize,
+}
+
+impl IntroSort {
+    /// Creates an IntroSort that finishes slices of at most `threshold` elements with insertion sort.
+    pub fn with_threshold(threshold: usize) -> Self {
+        IntroSort {
+            insertion_threshold: threshold.max(1),
+            stack: Vec::new(),
+            partition: None,
+            insertion: None,
+            heap: None,
+            special: (usize::MAX, usize::MAX),
+            phase: None,
+            reason: Reasons::Comparing,
+            started: false,
+            finished: false,
+            swaps: 0,
+            comparisons: 0,
+            writes: 0,
+        }
+    }
+
+    /// Returns the depth budget for a slice of `len` elements, `2 * floor(log2(len))`.
+    fn depth_limit(len: usize) -> usize {
+        2 * (usize::BITS - 1 - len.leading_zeros()) as usize
+    }
+
+    /// Moves the median of the first, middle and last elements of `low..high` to `low`,
+    /// where it serves as the pivot.
+    fn median_of_three(&mut self, array: &mut [usize], low: usize, high: usize) {
+        let mid = low + (high - low) / 2;
+        let last = high - 1;
+
+        self.comparisons += 3;
+        let median = if (array[low] <= array[mid]) == (array[mid] <= array[last]) {
+            mid
+        } else if (array[mid] <= array[low]) == (array[low] <= array[last]) {
+            low
+        } else {
+            last
+        };
+        if median != low {
+            array.swap(median, low);
+            self.swaps += 1;
+            self.writes += 2;
+            play_beep();
+        }
+    }
+
+    /// Pushes the slice `low..high` onto the stack if it still needs sorting.
+    fn push(&mut self, low: usize, high: usize, depth: usize) {
+        if high - low > 1 {
+            self.stack.push((low, high, depth));
+        }
+    }
+
+    /// Advances `partition` by one comparison, swapping once both scans have stopped.
+    /// # Returns
+    /// `true` once the scans have met and both sides are on the stack.
+    fn partition_step(&mut self, array: &mut [usize], partition: &mut Partition) -> bool {
+        self.comparisons += 1;
+        self.special = (partition.i, partition.j);
+        self.reason = Reasons::Comparing;
+        if partition.scanning_left {
+            if array[partition.i] < partition.pivot {
+                partition.i += 1;
+            } else {
+                partition.scanning_left = false;
+            }
+            return false;
+        }
+        if array[partition.j] > partition.pivot {
+            partition.j -= 1;
+            return false;
+        }
+        if partition.i >= partition.j {
+            // The scans met: `low..=j` and `j + 1..high` are the two sides.
+            let split = partition.j + 1;
+            self.push(partition.low, split, partition.depth);
+            self.push(split, partition.high, partition.depth);
+            return true;
+        }
+
+        array.swap(partition.i, partition.j);
+        self.swaps += 1;
+        self.writes += 2;
+        play_beep();
+        self.reason = Reasons::Switching;
+        partition.i += 1;
+        partition.j -= 1;
+        partition.scanning_left = true;
+        false
+    }
+}
+
+impl Sorter for IntroSort {
+    /// Creates a new instance of IntroSort.
+    fn new() -> Self {
+        Self::with_threshold(DEFAULT_INSERTION_THRESHOLD)
+    }
+
+    /// Returns the indices touched by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step of IntroSort: one comparison of a partition,
+    /// one heapsort step, or one insertion on a small slice.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+
+        if !self.started {
+            self.started = true;
+            if array.len() > 1 {
+                self.stack
+                    .push((0, array.len(), Self::depth_limit(array.len())));
+            }
+        }
+
+        // Continue a running partition or fallback before taking the next slice.
+        if let Some(mut partition) = self.partition.take() {
+            if !self.partition_step(array, &mut partition) {
+                self.partition = Some(partition);
+            }
+            return false;
+        }
+        if let Some(insertion) = self.insertion.as_mut() {
+            let done = insertion.step(
+                array,
+                &mut self.comparisons,
+                &mut self.swaps,
+                &mut self.writes,
+            );
+            self.special = insertion.focus();
+            self.reason = Reasons::Switching;
+            if done {
+                self.insertion = None;
+            }
+            return false;
+        }
+        if let Some(heap) = self.heap.as_mut() {
+            let done = heap.step(
+                array,
+                &mut self.comparisons,
+                &mut self.swaps,
+                &mut self.writes,
+            );
+            self.special = heap.focus();
+            self.reason = Reasons::Switching;
+            if done {
+                self.heap = None;
+            }
+            return false;
+        }
+
+        let Some((low, high, depth)) = self.stack.pop() else {
+            self.finished = true;
+            self.phase = None;
+            self.special = (usize::MAX, usize::MAX);
+            return true;
+        };
+
+        self.special = (low, high - 1);
+        self.reason = Reasons::Comparing;
+
+        if high - low <= self.insertion_threshold {
+            self.phase = Some("Insertion sort (small slice)");
+            self.insertion = Some(RangeInsertion::new(low, high));
+        } else if depth == 0 {
+            self.phase = Some("Heapsort fallback (depth limit reached)");
+            self.heap = Some(RangeHeap::new(low, high));
+        } else {
+            self.phase = Some("Quicksort partition");
+            self.median_of_three(array, low, high);
+            self.partition = Some(Partition {
+                low,
+                high,
+                depth: depth - 1,
+                i: low,
+                j: high - 1,
+                pivot: array[low],
+                scanning_left: true,
+            });
+        }
+
+        false
+    }
+
+    /// Resets the state of the IntroSort instance for a fresh sort.
+    fn reset_state(&mut self) {
+        *self = Self::with_threshold(self.insertion_threshold); // Keep the threshold.
+    }
+
+    /// Checks if the IntroSort process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Returns the strategy currently applied to the array.
+    fn phase(&self) -> Option<&'static str> {
+        self.phase
+    }
+}
@@ -4,6 +4,7 @@ use crate::sound::play_beep;
 /// A struct representing the MergeSort algorithm.
 /// It maintains the state of the sorting process, including subarray size, index, and a temporary array for merging.
 pub struct MergeSort {
+    run: usize,       // Length of the runs the first pass starts merging.
     size: usize,      // Current size of the subarrays being merged.
     index: usize,     // Index tracking the current position in the array.
     temp: Vec<usize>, // Temporary array used during merging.
@@ -11,9 +12,33 @@ pub struct MergeSort {
     is_sorted: bool,  // Indicates whether the sorting process is complete.
     swaps: usize,     // Indicates if the sorting is finished.
     comparisons: usize,
+    writes: usize,
 }
 
 impl MergeSort {
+    /// Creates a MergeSort whose first pass merges runs of `run` elements,
+    /// for when another algorithm has already sorted runs of that length.
+    pub fn with_run(run: usize) -> Self {
+        let run = run.max(1);
+        MergeSort {
+            run,
+            size: run,                  // Start merging subarrays of the run length.
+            index: 0,                   // Initialize index tracker.
+            temp: Vec::new(),           // Temporary array will be initialized during sorting.
+            reason: Reasons::Comparing, // Initial action reason is Comparing.
+            is_sorted: false,           // Sorting is not complete initially.
+            swaps: 0,                   // Indicates if the sorting is finished.
+            comparisons: 0,
+            writes: 0,
+        }
+    }
+
+    /// Returns the range `(start, end)` merged by the last step, if any.
+    pub(crate) fn range(&self) -> Option<(usize, usize)> {
+        let start = self.index.checked_sub(2 * self.size)?;
+        (!self.is_sorted).then(|| (start, (start + 2 * self.size).min(self.temp.len())))
+    }
+
     /// Merges two sorted subarrays into a single sorted subarray.
     /// # Arguments
     /// * `array` - The array containing the subarrays to be merged.
@@ -53,24 +78,15 @@ impl MergeSort {
         }
 
         // Copy merged elements back into the original array.
-        for i in start..=end {
-            array[i] = self.temp[i];
-        }
+        array[start..=end].copy_from_slice(&self.temp[start..=end]);
+        self.writes += end - start + 1;
     }
 }
 
 impl Sorter for MergeSort {
     /// Creates a new instance of MergeSort with initial settings.
     fn new() -> Self {
-        MergeSort {
-            size: 1,                    // Start merging subarrays of size 1.
-            index: 0,                   // Initialize index tracker.
-            temp: Vec::new(),           // Temporary array will be initialized during sorting.
-            reason: Reasons::Comparing, // Initial action reason is Comparing.
-            is_sorted: false,           // Sorting is not complete initially.
-            swaps: 0,                   // Indicates if the sorting is finished.
-            comparisons: 0,
-        }
+        Self::with_run(1) // Start merging subarrays of size 1.
     }
 
     /// Executes a single step of the MergeSort algorithm.
@@ -118,14 +134,17 @@ impl Sorter for MergeSort {
 
     /// Resets the state of the MergeSort instance for a new sorting process.
     fn reset_state(&mut self) {
-        *self = Self::new(); // Reset all fields to their initial state.
+        *self = Self::with_run(self.run); // Keep the run length.
     }
 
     /// Returns the range of indices currently being processed.
     fn special(&self) -> (usize, usize) {
         let n = self.temp.len();
-        let start = self.index.min(n);
-        let end = (start + self.size).min(n);
+        if self.is_sorted || self.index >= n {
+            return (usize::MAX, usize::MAX); // No range is left in this pass.
+        }
+        let start = self.index;
+        let end = (start + self.size).min(n - 1);
         (start, end) // Highlight the current range being merged.
     }
 
@@ -145,4 +164,12 @@ impl Sorter for MergeSort {
     fn swaps(&self) -> usize {
         self.swaps
     }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    fn auxiliary_memory(&self) -> usize {
+        self.temp.len()
+    }
 }
@@ -1,29 +1,74 @@
+pub mod bitonic_sort;
+pub mod block_merge_sort;
 pub mod bogo_sort;
 pub mod bubble_sort;
+pub mod bucket_sort;
 pub mod cocktail_sort;
 pub mod comb_sort;
+#[cfg(test)]
+mod conformance;
 pub mod counting_sort;
+pub mod cycle_sort;
 pub mod gnome_sort;
+#[cfg(test)]
+mod golden;
 pub mod heap_sort;
+pub mod heap_top_k;
+pub mod hybrid_sort;
 pub mod insertion_sort;
+pub mod intro_sort;
 pub mod merge_sort;
+pub mod odd_even_merge_sort;
 pub mod odd_even_sort;
 pub mod pancake_sort;
+pub mod partial_insertion_sort;
+pub mod patience_sort;
+pub mod pdq_sort;
+pub mod permutation_sort;
+#[cfg(test)]
+mod properties;
+pub mod quick_select;
 pub mod quick_sort;
+pub mod rotation_merge_sort;
 pub mod selection_sort;
 pub mod shell_sort;
+pub mod slow_sort;
+pub mod smooth_sort;
+mod sorting_network;
+pub mod stooge_sort;
+pub mod structure;
+mod subrange;
+pub mod top_down_merge_sort;
+pub mod tree_sort;
+
+use serde::{Deserialize, Serialize};
+use structure::Structure;
+
+/// Number of steps an algorithm with a step estimate may be expected to take
+/// before its input is capped.
+pub const STEP_BUDGET: f64 = 50_000.0;
 
 /// Enum representing the reasons for sorting actions.
 /// * `Comparing` - Indicates that elements are being compared.
 /// * `Switching` - Indicates that elements are being swapped.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Reasons {
     Comparing,
     Switching,
 }
 
+/// A range of the array handled by one of the algorithms a hybrid sorter combines.
+pub struct Region {
+    pub low: usize,        // First index of the range.
+    pub high: usize,       // One past the last index of the range.
+    pub algorithm: String, // Name of the algorithm handling the range.
+    pub handed_off: bool,  // Whether the range was handed to the inner algorithm.
+    pub active: bool,      // Whether the last step was taken in this range.
+}
+
 /// A trait representing the behavior of a sorting algorithm.
-pub trait Sorter {
+/// Sorters are `Send`, so the visualizer can step them on a worker thread.
+pub trait Sorter: Send {
     /// Creates a new instance of the sorting algorithm.
     /// # Returns a new instance of the sorter.
     fn new() -> Self
@@ -57,4 +102,98 @@ pub trait Sorter {
     fn comparisons(&self) -> usize;
 
     fn swaps(&self) -> usize;
+
+    /// # Returns
+    /// The number of element writes to the array, where a swap counts as two writes.
+    fn writes(&self) -> usize;
+
+    /// # Returns
+    /// The number of elements currently held in buffers outside the array.
+    /// Bookkeeping such as index stacks is not counted.
+    fn auxiliary_memory(&self) -> usize {
+        0
+    }
+
+    /// Describes the strategy currently in use, for algorithms that switch between several.
+    /// # Returns
+    /// A short label such as `"Heapsort fallback"`, or `None` if the algorithm has a single strategy.
+    fn phase(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// # Returns
+    /// The indices of the current pivot elements, highlighted separately from `special()`.
+    fn pivots(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    /// # Returns
+    /// Additional indices to highlight alongside `special()`, each with its own reason.
+    /// Used by algorithms that touch many elements in a single step.
+    fn highlights(&self) -> Vec<(usize, Reasons)> {
+        Vec::new()
+    }
+
+    /// # Returns
+    /// Pairs of indices to draw linked in the bar view, such as the elements a gap connects.
+    fn links(&self) -> Vec<(usize, usize)> {
+        Vec::new()
+    }
+
+    /// Estimates the steps needed to sort `len` elements, for algorithms so slow that
+    /// the estimate is shown before starting and their input is capped to `STEP_BUDGET`.
+    /// # Returns
+    /// The estimated number of steps, or `None` for algorithms of ordinary speed.
+    fn estimated_steps(&self, _len: usize) -> Option<f64> {
+        None
+    }
+
+    /// # Returns
+    /// The positions `low..high` a selection or top-k algorithm must finalize,
+    /// or `None` for algorithms that sort the whole array.
+    fn target(&self) -> Option<(usize, usize)> {
+        None
+    }
+
+    /// # Returns
+    /// Positions already holding their final sorted value, for algorithms that
+    /// leave the rest of the array unsorted.
+    fn finalized(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    /// # Returns
+    /// The ranges a hybrid sorter has handed between its algorithms, so the handoff can be drawn.
+    fn regions(&self) -> Vec<Region> {
+        Vec::new()
+    }
+
+    /// # Returns
+    /// An auxiliary data structure to draw next to the bars, or `None` if the algorithm has none.
+    fn structure(&self) -> Option<Structure<'_>> {
+        None
+    }
+}
+
+/// Returns how many of `len` elements `sorter` may be given so that its estimated
+/// step count stays within `STEP_BUDGET`. Algorithms without an estimate take every element.
+pub fn input_limit(sorter: &dyn Sorter, len: usize) -> usize {
+    input_limit_within(sorter, len, STEP_BUDGET)
+}
+
+/// Returns how many of `len` elements `sorter` may be given so that its estimated
+/// step count stays within `budget`.
+pub fn input_limit_within(sorter: &dyn Sorter, len: usize, budget: f64) -> usize {
+    let within_budget = |n: usize| {
+        sorter
+            .estimated_steps(n)
+            .is_none_or(|steps| steps <= budget)
+    };
+    if within_budget(len) {
+        return len;
+    }
+    (1..len)
+        .take_while(|&n| within_budget(n))
+        .last()
+        .unwrap_or(1)
 }
@@ -5,9 +5,11 @@ pub struct OddEvenSort {
     is_sorted: bool,
     odd_phase: bool,
     i: usize,
+    special: (usize, usize), // The pair compared by the last step.
     finished: bool,
     swaps: usize, // Indicates if the sorting is finished.
     comparisons: usize,
+    writes: usize,
 }
 
 impl OddEvenSort {
@@ -16,13 +18,21 @@ impl OddEvenSort {
             is_sorted: false,
             odd_phase: true,
             i: 1,
+            special: (usize::MAX, usize::MAX),
             finished: false, // Sorting is not finished initially.
             comparisons: 0,
+            writes: 0,
             swaps: 0,
         }
     }
 }
 
+impl Default for OddEvenSort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Sorter for OddEvenSort {
     fn new() -> Self {
         Self::new()
@@ -32,7 +42,7 @@ impl Sorter for OddEvenSort {
         if self.finished {
             (usize::MAX, usize::MAX)
         } else {
-            (self.i, self.i + 1)
+            self.special
         }
     }
 
@@ -49,7 +59,7 @@ impl Sorter for OddEvenSort {
             return true;
         }
 
-        if self.i >= array.len() - 1 {
+        if self.i + 1 >= array.len() {
             // If a full pass is done, check if sorted
             if self.is_sorted {
                 self.finished = true;
@@ -62,12 +72,15 @@ impl Sorter for OddEvenSort {
             self.i = if self.odd_phase { 1 } else { 0 };
         }
 
-        if self.i < array.len() - 1 {
-            play_beep();
-            self.swaps += 1; // Sound effect for visualization
+        if self.i + 1 < array.len() {
+            play_beep(); // Sound effect for visualization
+            self.comparisons += 1;
+            self.special = (self.i, self.i + 1);
 
             if array[self.i] > array[self.i + 1] {
                 array.swap(self.i, self.i + 1);
+                self.swaps += 1;
+                self.writes += 2;
                 self.is_sorted = false;
             }
 
@@ -91,4 +104,8 @@ impl Sorter for OddEvenSort {
     fn swaps(&self) -> usize {
         self.swaps
     }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
 }
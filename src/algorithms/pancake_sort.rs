@@ -6,6 +6,7 @@ pub struct PancakeSort {
     finished: bool,
     swaps: usize, // Indicates if the sorting is finished.
     comparisons: usize,
+    writes: usize,
 }
 
 impl PancakeSort {
@@ -14,12 +15,15 @@ impl PancakeSort {
             curr_size: 0,    // Will be initialized later
             finished: false, // Sorting is not finished initially.
             comparisons: 0,
+            writes: 0,
             swaps: 0,
         }
     }
 
-    fn flip(arr: &mut Vec<usize>, k: usize) {
+    /// Reverses `arr[..=k]` and returns the number of element writes.
+    fn flip(arr: &mut [usize], k: usize) -> usize {
         arr[..=k].reverse();
+        2 * k.div_ceil(2)
     }
 
     fn find_max(arr: &[usize], n: usize) -> usize {
@@ -27,6 +31,12 @@ impl PancakeSort {
     }
 }
 
+impl Default for PancakeSort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Sorter for PancakeSort {
     fn new() -> Self {
         Self::new()
@@ -69,9 +79,9 @@ impl Sorter for PancakeSort {
 
         if max_index != self.curr_size - 1 {
             if max_index > 0 {
-                Self::flip(array, max_index);
+                self.writes += Self::flip(array, max_index);
             }
-            Self::flip(array, self.curr_size - 1);
+            self.writes += Self::flip(array, self.curr_size - 1);
         }
 
         self.curr_size -= 1;
@@ -93,4 +103,8 @@ impl Sorter for PancakeSort {
     fn swaps(&self) -> usize {
         self.swaps
     }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
 }
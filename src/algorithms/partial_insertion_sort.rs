@@ -0,0 +1,176 @@
+use super::heap_top_k::{percent_count, DEFAULT_K_PERCENT};
+use super::subrange::RangeInsertion;
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+
+/// Represents partial insertion sort and its state.
+/// The first k elements are insertion sorted, then every later element smaller than
+/// the largest of them is inserted into that sorted prefix, pushing the largest out.
+/// Once every element has been seen, the prefix holds the k smallest in order.
+pub struct PartialInsertionSort {
+    percent: usize,                 // Share of the elements to sort, as a percentage.
+    k: usize,                       // Length of the sorted prefix, resolved at the first step.
+    prefix: Option<RangeInsertion>, // Insertion sort of the first k elements.
+    next: usize,                    // Next element to compare with the end of the prefix.
+    special: (usize, usize),        // Indices touched by the last step.
+    reason: Reasons,                // Reason for the current operation.
+    started: bool,                  // Whether k has been resolved.
+    finished: bool,                 // Indicates whether the prefix is final.
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+}
+
+impl PartialInsertionSort {
+    /// Creates a partial insertion sort of the smallest `percent`% of the elements.
+    pub fn with_percent(percent: usize) -> Self {
+        PartialInsertionSort {
+            percent,
+            k: 0,
+            prefix: None,
+            next: 0,
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            started: false,
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+
+    /// Compares the next element with the end of the prefix and, if it is smaller,
+    /// swaps it with that end and shifts it down to its place in the prefix.
+    fn insert_step(&mut self, array: &mut [usize]) {
+        let last = self.k - 1;
+        self.comparisons += 1;
+        self.special = (last, self.next);
+        self.reason = Reasons::Comparing;
+        if array[self.next] < array[last] {
+            array.swap(last, self.next);
+            self.swaps += 1;
+            self.writes += 2;
+
+            let value = array[last];
+            let mut i = last;
+            while i > 0 && {
+                self.comparisons += 1;
+                array[i - 1] > value
+            } {
+                array[i] = array[i - 1]; // Shift the larger element to the right.
+                self.writes += 1;
+                i -= 1;
+            }
+            array[i] = value;
+            self.writes += 1;
+            self.special = (i, self.next);
+            self.reason = Reasons::Switching;
+            play_beep();
+        }
+        self.next += 1;
+    }
+}
+
+impl Sorter for PartialInsertionSort {
+    /// Creates a partial insertion sort of the smallest 10% of the elements.
+    fn new() -> Self {
+        Self::with_percent(DEFAULT_K_PERCENT)
+    }
+
+    /// Returns the indices touched by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step: inserts one element while sorting the prefix,
+    /// or checks one later element against the end of the prefix.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+        if !self.started {
+            self.started = true;
+            self.k = percent_count(self.percent, array.len());
+            self.next = self.k;
+            self.prefix = Some(RangeInsertion::new(0, self.k));
+        }
+
+        if let Some(prefix) = &mut self.prefix {
+            let swaps = self.swaps;
+            let done = prefix.step(
+                array,
+                &mut self.comparisons,
+                &mut self.swaps,
+                &mut self.writes,
+            );
+            self.special = prefix.focus();
+            self.reason = if self.swaps > swaps {
+                Reasons::Switching
+            } else {
+                Reasons::Comparing
+            };
+            if done {
+                self.prefix = None;
+            }
+            return false;
+        }
+
+        if self.k == 0 || self.next >= array.len() {
+            self.finished = true;
+            self.special = (usize::MAX, usize::MAX);
+            return true;
+        }
+        self.insert_step(array);
+        false
+    }
+
+    /// Resets the state for a fresh sort, keeping the share of elements to sort.
+    fn reset_state(&mut self) {
+        *self = Self::with_percent(self.percent);
+    }
+
+    /// Checks if the sorted prefix is final.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Returns whether the prefix is being sorted or the rest scanned.
+    fn phase(&self) -> Option<&'static str> {
+        match (self.finished, self.prefix.is_some()) {
+            (true, _) => None,
+            (false, true) => Some("Sorting the first k"),
+            (false, false) => Some("Inserting smaller elements"),
+        }
+    }
+
+    /// Returns the first k positions.
+    fn target(&self) -> Option<(usize, usize)> {
+        self.started.then_some((0, self.k))
+    }
+
+    /// Returns the first k positions once every element has been seen.
+    fn finalized(&self) -> Vec<usize> {
+        if self.finished {
+            (0..self.k).collect()
+        } else {
+            Vec::new()
+        }
+    }
+}
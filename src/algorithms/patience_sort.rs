@@ -0,0 +1,187 @@
+use super::structure::Structure;
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+use std::borrow::Cow;
+
+/// Represents the PatienceSort algorithm and its state.
+/// Elements are dealt like cards onto piles, each going on the leftmost pile whose top
+/// is not smaller, so every pile decreases from bottom to top and the tops increase
+/// from left to right. A k-way merge of the pile tops then writes the values back.
+pub struct PatienceSort {
+    piles: Vec<Vec<usize>>,  // The piles, with the top card last.
+    next: usize,             // Next array element to deal while dealing.
+    output: usize,           // Next array index to write while merging.
+    dealing: bool,           // Whether the elements are still being dealt.
+    focus: Option<usize>,    // Pile touched by the last step.
+    special: (usize, usize), // Indices touched by the last step.
+    reason: Reasons,         // Reason for the current operation.
+    finished: bool,          // Indicates whether the sorting is complete.
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+}
+
+impl PatienceSort {
+    /// Deals the next element onto the leftmost pile whose top is not smaller,
+    /// found by binary search over the increasing pile tops.
+    fn deal_step(&mut self, array: &[usize]) {
+        let value = array[self.next];
+        let (mut low, mut high) = (0, self.piles.len());
+        while low < high {
+            let middle = (low + high) / 2;
+            self.comparisons += 1;
+            if self.top(middle) < value {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        if low == self.piles.len() {
+            self.piles.push(Vec::new());
+        }
+        self.piles[low].push(value);
+        self.focus = Some(low);
+        self.special = (self.next, self.next);
+        self.reason = Reasons::Comparing;
+        self.next += 1;
+    }
+
+    /// Moves the smallest pile top to the next output position.
+    /// # Returns
+    /// `true` once every pile is empty.
+    fn merge_step(&mut self, array: &mut [usize]) -> bool {
+        let mut smallest: Option<usize> = None;
+        for pile in 0..self.piles.len() {
+            if self.piles[pile].is_empty() {
+                continue;
+            }
+            smallest = match smallest {
+                Some(best) => {
+                    self.comparisons += 1;
+                    if self.top(pile) < self.top(best) {
+                        Some(pile)
+                    } else {
+                        Some(best)
+                    }
+                }
+                None => Some(pile),
+            };
+        }
+        let Some(pile) = smallest else {
+            return true;
+        };
+
+        array[self.output] = self.piles[pile].pop().unwrap_or_default();
+        self.writes += 1;
+        self.focus = Some(pile);
+        self.special = (self.output, self.output);
+        self.reason = Reasons::Switching;
+        play_beep();
+        self.output += 1;
+        false
+    }
+
+    /// Returns the top card of a non-empty pile.
+    fn top(&self, pile: usize) -> usize {
+        *self.piles[pile].last().unwrap_or(&usize::MAX)
+    }
+}
+
+impl Sorter for PatienceSort {
+    /// Creates a new instance of PatienceSort.
+    fn new() -> Self {
+        PatienceSort {
+            piles: Vec::new(),
+            next: 0,
+            output: 0,
+            dealing: true,
+            focus: None,
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+
+    /// Returns the indices touched by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step: deals one element onto a pile,
+    /// or merges one pile top back into the array.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+
+        if self.dealing && self.next < array.len() {
+            self.deal_step(array);
+            return false;
+        }
+        self.dealing = false;
+
+        if self.merge_step(array) {
+            self.finished = true;
+            self.focus = None;
+            self.special = (usize::MAX, usize::MAX);
+            return true;
+        }
+        false
+    }
+
+    /// Resets the state of the PatienceSort instance for a fresh sort.
+    fn reset_state(&mut self) {
+        *self = Self::new(); // Reset all fields to their initial state.
+    }
+
+    /// Checks if the sorting process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    fn auxiliary_memory(&self) -> usize {
+        self.piles.iter().map(Vec::len).sum()
+    }
+
+    /// Returns whether the elements are being dealt or merged.
+    fn phase(&self) -> Option<&'static str> {
+        match (self.dealing, self.finished) {
+            (_, true) => None,
+            (true, false) => Some("Dealing into piles"),
+            (false, false) => Some("Merging pile tops"),
+        }
+    }
+
+    /// Returns the piles.
+    fn structure(&self) -> Option<Structure<'_>> {
+        if self.next == 0 {
+            return None;
+        }
+        Some(Structure::Piles {
+            piles: Cow::Borrowed(&self.piles),
+            focus: self.focus,
+            dealing: self.dealing,
+        })
+    }
+}
@@ -0,0 +1,477 @@
+use super::subrange::{RangeHeap, RangeInsertion};
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+
+/// Slices with at most this many elements are finished with insertion sort.
+const MAX_INSERTION: usize = 20;
+
+/// Slices with at least this many elements use Tukey's ninther as pivot.
+const SHORTEST_MEDIAN_OF_MEDIANS: usize = 50;
+
+/// Maximum number of index swaps while choosing a pivot before the slice is reversed.
+const MAX_SWAPS: usize = 4 * 3;
+
+/// Maximum number of adjacent out-of-order pairs fixed by partial insertion sort.
+const MAX_PARTIAL_STEPS: usize = 5;
+
+/// Slices shorter than this are not shifted by partial insertion sort.
+const SHORTEST_SHIFTING: usize = 50;
+
+/// A pending slice together with the bookkeeping pdqsort carries between partitions.
+#[derive(Clone, Copy)]
+struct Task {
+    low: usize,            // First index of the slice.
+    high: usize,           // One past the last index of the slice.
+    pred: Option<usize>,   // Value of the element just before the slice, if any.
+    limit: usize,          // Imbalanced partitions allowed before falling back to heapsort.
+    was_balanced: bool,    // Whether the partition that produced this slice was balanced.
+    was_partitioned: bool, // Whether that partition found the slice already partitioned.
+}
+
+/// Represents a pattern-defeating QuickSort, modelled on Rust's `sort_unstable`.
+/// It detects sorted and reversed runs, groups elements equal to the pivot,
+/// shuffles elements to break up adversarial patterns, and falls back to
+/// HeapSort when too many partitions are unbalanced.
+pub struct PdqSort {
+    stack: Vec<Task>,                  // Pending slices.
+    insertion: Option<RangeInsertion>, // Active insertion sort on a small slice.
+    heap: Option<RangeHeap>,           // Active heapsort fallback.
+    special: (usize, usize),           // Indices highlighted by the last step.
+    phase: Option<&'static str>,       // Strategy currently applied to the array.
+    reason: Reasons,                   // Reason for the current operation.
+    started: bool,                     // Whether the initial slice has been pushed.
+    finished: bool,                    // Indicates whether the sorting is complete.
+    swaps: usize,
+    comparisons: usize,
+    writes: usize,
+}
+
+impl PdqSort {
+    /// Orders the indices `a` and `b` by the values they point at.
+    fn sort2(&mut self, array: &[usize], a: &mut usize, b: &mut usize, swaps: &mut usize) {
+        self.comparisons += 1;
+        if array[*b] < array[*a] {
+            std::mem::swap(a, b);
+            *swaps += 1;
+        }
+    }
+
+    /// Orders the indices `a`, `b` and `c` by the values they point at.
+    fn sort3(
+        &mut self,
+        array: &[usize],
+        a: &mut usize,
+        b: &mut usize,
+        c: &mut usize,
+        swaps: &mut usize,
+    ) {
+        self.sort2(array, a, b, swaps);
+        self.sort2(array, b, c, swaps);
+        self.sort2(array, a, b, swaps);
+    }
+
+    /// Replaces `a` with the index of the median of `a - 1`, `a` and `a + 1`.
+    fn sort_adjacent(&mut self, array: &[usize], a: &mut usize, swaps: &mut usize) {
+        let (mut left, mut right) = (*a - 1, *a + 1);
+        self.sort3(array, &mut left, a, &mut right, swaps);
+    }
+
+    /// Chooses a pivot in `low..high`, reversing the slice if it looks descending.
+    /// # Returns
+    /// The pivot index and whether the slice is likely already sorted.
+    fn choose_pivot(&mut self, array: &mut [usize], low: usize, high: usize) -> (usize, bool) {
+        let len = high - low;
+        let mut a = low + len / 4;
+        let mut b = low + len / 4 * 2;
+        let mut c = low + len / 4 * 3;
+        let mut swaps = 0;
+
+        if len >= 8 {
+            if len >= SHORTEST_MEDIAN_OF_MEDIANS {
+                self.sort_adjacent(array, &mut a, &mut swaps);
+                self.sort_adjacent(array, &mut b, &mut swaps);
+                self.sort_adjacent(array, &mut c, &mut swaps);
+            }
+            self.sort3(array, &mut a, &mut b, &mut c, &mut swaps);
+        }
+
+        if swaps < MAX_SWAPS {
+            (b, swaps == 0)
+        } else {
+            // Every comparison disagreed, so the slice is most likely descending.
+            array[low..high].reverse();
+            self.swaps += len / 2;
+            self.writes += 2 * (len / 2);
+            play_beep();
+            (high - 1 - (b - low), true)
+        }
+    }
+
+    /// Fixes up to a few out-of-order adjacent pairs in `low..high`.
+    /// # Returns
+    /// `true` if the slice ends up fully sorted.
+    fn partial_insertion_sort(&mut self, array: &mut [usize], low: usize, high: usize) -> bool {
+        let len = high - low;
+        let mut i = low + 1;
+
+        for _ in 0..MAX_PARTIAL_STEPS {
+            while i < high {
+                self.comparisons += 1;
+                if array[i] < array[i - 1] {
+                    break;
+                }
+                i += 1;
+            }
+            if i == high {
+                return true;
+            }
+            if len < SHORTEST_SHIFTING {
+                return false;
+            }
+
+            array.swap(i - 1, i);
+            self.swaps += 1;
+            self.writes += 2;
+            play_beep();
+
+            // Shift the smaller element left into the sorted prefix.
+            let mut j = i - 1;
+            while j > low {
+                self.comparisons += 1;
+                if array[j] >= array[j - 1] {
+                    break;
+                }
+                array.swap(j, j - 1);
+                self.swaps += 1;
+                self.writes += 2;
+                j -= 1;
+            }
+
+            // Shift the larger element right into the remainder.
+            let mut k = i;
+            while k + 1 < high {
+                self.comparisons += 1;
+                if array[k + 1] >= array[k] {
+                    break;
+                }
+                array.swap(k, k + 1);
+                self.swaps += 1;
+                self.writes += 2;
+                k += 1;
+            }
+        }
+
+        false
+    }
+
+    /// Swaps a few elements around the middle of `low..high` to break up patterns
+    /// that caused an unbalanced partition.
+    fn break_patterns(&mut self, array: &mut [usize], low: usize, high: usize) {
+        let len = high - low;
+        if len < 8 {
+            return;
+        }
+
+        let mut random = len as u32;
+        let mut next = || {
+            // Xorshift generator seeded with the slice length, as in `sort_unstable`.
+            random ^= random << 13;
+            random ^= random >> 17;
+            random ^= random << 5;
+            random as usize
+        };
+
+        let modulus = len.next_power_of_two();
+        let pos = len / 4 * 2;
+        for i in 0..3 {
+            let mut other = next() & (modulus - 1);
+            if other >= len {
+                other -= len;
+            }
+            array.swap(low + pos - 1 + i, low + other);
+            self.swaps += 1;
+            self.writes += 2;
+        }
+        play_beep();
+    }
+
+    /// Partitions `low..high` into elements smaller than the pivot and the rest.
+    /// # Returns
+    /// The final pivot index and whether the slice was already partitioned.
+    fn partition(
+        &mut self,
+        array: &mut [usize],
+        low: usize,
+        high: usize,
+        pivot: usize,
+    ) -> (usize, bool) {
+        array.swap(low, pivot);
+        let value = array[low];
+        let mut store = low + 1;
+        let mut moved = false;
+
+        for k in low + 1..high {
+            self.comparisons += 1;
+            if array[k] < value {
+                if k != store {
+                    array.swap(store, k);
+                    self.swaps += 1;
+                    self.writes += 2;
+                    moved = true;
+                }
+                store += 1;
+            }
+        }
+
+        let mid = store - 1;
+        if mid != low {
+            array.swap(low, mid);
+            self.swaps += 1;
+            self.writes += 2;
+        }
+        play_beep();
+        (mid, !moved)
+    }
+
+    /// Moves every element equal to the pivot to the front of `low..high`.
+    /// Only called when the predecessor equals the pivot, so nothing is smaller.
+    /// # Returns
+    /// The index of the first element greater than the pivot.
+    fn partition_equal(
+        &mut self,
+        array: &mut [usize],
+        low: usize,
+        high: usize,
+        pivot: usize,
+    ) -> usize {
+        array.swap(low, pivot);
+        let value = array[low];
+        let mut store = low + 1;
+
+        for k in low + 1..high {
+            self.comparisons += 1;
+            if array[k] <= value {
+                if k != store {
+                    array.swap(store, k);
+                    self.swaps += 1;
+                    self.writes += 2;
+                }
+                store += 1;
+            }
+        }
+
+        play_beep();
+        store
+    }
+
+    /// Pushes a slice unless it is already trivially sorted.
+    fn push(&mut self, task: Task) {
+        if task.high - task.low > 1 {
+            self.stack.push(task);
+        }
+    }
+}
+
+impl Sorter for PdqSort {
+    /// Creates a new instance of PdqSort.
+    fn new() -> Self {
+        PdqSort {
+            stack: Vec::new(),
+            insertion: None,
+            heap: None,
+            special: (usize::MAX, usize::MAX),
+            phase: None,
+            reason: Reasons::Comparing,
+            started: false,
+            finished: false,
+            swaps: 0,
+            comparisons: 0,
+            writes: 0,
+        }
+    }
+
+    /// Returns the indices touched by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step of PdqSort: one pdqsort decision on the next slice,
+    /// or one step of an active insertion sort or heapsort fallback.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+
+        if !self.started {
+            self.started = true;
+            let len = array.len();
+            self.push(Task {
+                low: 0,
+                high: len,
+                pred: None,
+                limit: (usize::BITS - len.leading_zeros()) as usize,
+                was_balanced: true,
+                was_partitioned: true,
+            });
+        }
+
+        // Continue a running fallback before taking the next slice.
+        if let Some(insertion) = self.insertion.as_mut() {
+            let done = insertion.step(
+                array,
+                &mut self.comparisons,
+                &mut self.swaps,
+                &mut self.writes,
+            );
+            self.special = insertion.focus();
+            self.reason = Reasons::Switching;
+            if done {
+                self.insertion = None;
+            }
+            return false;
+        }
+        if let Some(heap) = self.heap.as_mut() {
+            let done = heap.step(
+                array,
+                &mut self.comparisons,
+                &mut self.swaps,
+                &mut self.writes,
+            );
+            self.special = heap.focus();
+            self.reason = Reasons::Switching;
+            if done {
+                self.heap = None;
+            }
+            return false;
+        }
+
+        let Some(task) = self.stack.pop() else {
+            self.finished = true;
+            self.phase = None;
+            self.special = (usize::MAX, usize::MAX);
+            return true;
+        };
+        let (low, high) = (task.low, task.high);
+        let len = high - low;
+
+        self.special = (low, high - 1);
+        self.reason = Reasons::Comparing;
+
+        if len <= MAX_INSERTION {
+            self.phase = Some("Insertion sort (small slice)");
+            self.insertion = Some(RangeInsertion::new(low, high));
+            return false;
+        }
+
+        if task.limit == 0 {
+            self.phase = Some("Heapsort fallback (too many bad partitions)");
+            self.heap = Some(RangeHeap::new(low, high));
+            return false;
+        }
+
+        if !task.was_balanced {
+            self.phase = Some("Breaking patterns (unbalanced partition)");
+            self.break_patterns(array, low, high);
+            self.reason = Reasons::Switching;
+            self.push(Task {
+                limit: task.limit - 1,
+                was_balanced: true,
+                was_partitioned: false,
+                ..task
+            });
+            return false;
+        }
+
+        let (pivot, likely_sorted) = self.choose_pivot(array, low, high);
+
+        if task.was_partitioned && likely_sorted {
+            if self.partial_insertion_sort(array, low, high) {
+                self.phase = Some("Partial insertion sort (already sorted)");
+                self.reason = Reasons::Switching;
+                return false;
+            }
+            // Retry the slice as an ordinary partition.
+            self.phase = Some("Partial insertion sort gave up");
+            self.push(Task {
+                was_partitioned: false,
+                ..task
+            });
+            return false;
+        }
+
+        if let Some(pred) = task.pred {
+            if pred >= array[pivot] {
+                // The pivot equals its predecessor, so group its duplicates and skip them.
+                self.phase = Some("Partition equal elements");
+                let mid = self.partition_equal(array, low, high, pivot);
+                self.special = (low, mid - 1);
+                self.reason = Reasons::Switching;
+                self.push(Task { low: mid, ..task });
+                return false;
+            }
+        }
+
+        self.phase = Some("Partition");
+        let (mid, was_partitioned) = self.partition(array, low, high, pivot);
+        let was_balanced = (mid - low).min(high - mid) >= len / 8;
+        self.special = (mid, mid);
+        self.reason = Reasons::Switching;
+
+        let left = Task {
+            high: mid,
+            was_balanced,
+            was_partitioned,
+            ..task
+        };
+        let right = Task {
+            low: mid + 1,
+            pred: Some(array[mid]),
+            was_balanced,
+            was_partitioned,
+            ..task
+        };
+
+        // Push the larger side first so the smaller one is handled next.
+        if mid - low < high - mid {
+            self.push(right);
+            self.push(left);
+        } else {
+            self.push(left);
+            self.push(right);
+        }
+
+        false
+    }
+
+    /// Resets the state of the PdqSort instance for a fresh sort.
+    fn reset_state(&mut self) {
+        *self = Self::new(); // Reset all fields to their initial state.
+    }
+
+    /// Checks if the PdqSort process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Returns the strategy currently applied to the array.
+    fn phase(&self) -> Option<&'static str> {
+        self.phase
+    }
+}
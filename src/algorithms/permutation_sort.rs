@@ -0,0 +1,141 @@
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+
+/// Returns `n!` as a floating-point number, so large values saturate instead of overflowing.
+pub fn factorial(n: usize) -> f64 {
+    (2..=n).map(|i| i as f64).product()
+}
+
+/// Represents PermutationSort, a deterministic BogoSort and its state.
+/// Instead of shuffling at random, it enumerates every arrangement of the array with
+/// Heap's algorithm, which moves from one permutation to the next with a single swap,
+/// and stops at the first sorted one. That takes up to n! steps.
+pub struct PermutationSort {
+    counters: Vec<usize>,    // Heap's algorithm: swaps made at each level so far.
+    level: usize,            // Heap's algorithm: the level to advance next.
+    checked: bool,           // Whether the initial arrangement has been checked.
+    special: (usize, usize), // Indices swapped by the last step.
+    reason: Reasons,         // Reason for the current operation.
+    finished: bool,          // Indicates whether the sorting is complete.
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+}
+
+impl PermutationSort {
+    /// Checks whether the array is sorted, stopping at the first pair out of order.
+    fn is_sorted_check(&mut self, array: &[usize]) -> bool {
+        for pair in array.windows(2) {
+            self.comparisons += 1;
+            if pair[0] > pair[1] {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Advances Heap's algorithm to the next permutation with a single swap.
+    /// # Returns
+    /// `false` if every permutation has already been visited.
+    fn next_permutation(&mut self, array: &mut [usize]) -> bool {
+        while self.level < array.len() {
+            let level = self.level;
+            if self.counters[level] < level {
+                let other = if level.is_multiple_of(2) {
+                    0
+                } else {
+                    self.counters[level]
+                };
+                array.swap(other, level);
+                self.swaps += 1;
+                self.writes += 2;
+                self.special = (other, level);
+                self.counters[level] += 1;
+                self.level = 1;
+                return true;
+            }
+            self.counters[level] = 0;
+            self.level += 1;
+        }
+        false
+    }
+}
+
+impl Sorter for PermutationSort {
+    /// Creates a new instance of PermutationSort.
+    fn new() -> Self {
+        PermutationSort {
+            counters: Vec::new(),
+            level: 1,
+            checked: false,
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+
+    /// Returns the indices swapped by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step: moves to the next permutation and checks whether it is sorted.
+    /// The first step only checks the initial arrangement.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+
+        if !self.checked {
+            self.checked = true;
+            self.counters = vec![0; array.len()];
+            self.reason = Reasons::Comparing;
+        } else if self.next_permutation(array) {
+            self.reason = Reasons::Switching;
+            play_beep();
+        }
+
+        // Every arrangement is visited eventually, so the sorted one is always found.
+        if self.is_sorted_check(array) {
+            self.finished = true;
+            self.special = (usize::MAX, usize::MAX);
+            return true;
+        }
+        false
+    }
+
+    /// Resets the state of the PermutationSort instance for a fresh sort.
+    fn reset_state(&mut self) {
+        *self = Self::new(); // Reset all fields to their initial state.
+    }
+
+    /// Checks if the sorting process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// The worst case: the sorted arrangement is the last of the n! visited.
+    fn estimated_steps(&self, len: usize) -> Option<f64> {
+        Some(factorial(len))
+    }
+}
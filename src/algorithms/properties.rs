@@ -0,0 +1,54 @@
+//! Property tests running every `Sorter` on arbitrary vectors. Failing inputs are shrunk
+//! by proptest to a minimal case, which is printed with the failure.
+
+use super::conformance::{check_result, counters, run, sorters};
+use super::input_limit;
+use crate::sound;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+/// Arbitrary inputs: vectors of few distinct values, full of duplicates, or of values
+/// spread widely. Lengths reach past the 50 elements some algorithms need before they take
+/// their special paths. Values stay small because CountingSort allocates one count per value.
+fn arbitrary_input() -> impl Strategy<Value = Vec<usize>> {
+    prop_oneof![vec(0usize..4, 0..300), vec(0usize..1000, 0..300)]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(48))]
+
+    #[test]
+    fn sorters_sort_arbitrary_inputs(input in arbitrary_input()) {
+        for (algorithm, mut sorter) in sorters() {
+            let input = &input[..input_limit(sorter.as_ref(), input.len())];
+            let context = format!("{} on {:?}", algorithm.label(), input);
+            let mut array = input.to_vec();
+            run(sorter.as_mut(), &mut array, &context);
+            check_result(algorithm, sorter.as_ref(), input, &array, &context);
+        }
+    }
+
+    #[test]
+    fn sorters_recover_from_a_reset_midway(input in arbitrary_input(), steps in 0usize..100) {
+        for (algorithm, mut sorter) in sorters() {
+            let input = &input[..input_limit(sorter.as_ref(), input.len())];
+            let context = format!("{} on {:?} reset after {} steps", algorithm.label(), input, steps);
+
+            // Abandon a sort partway, then sort the original input from scratch.
+            let mut array = input.to_vec();
+            sound::muted(|| {
+                for _ in 0..steps {
+                    if sorter.step(&mut array) {
+                        break;
+                    }
+                }
+            });
+            sorter.reset_state();
+            prop_assert_eq!(counters(sorter.as_ref()), (0, 0, 0), "{}: counters kept", context);
+
+            let mut array = input.to_vec();
+            run(sorter.as_mut(), &mut array, &context);
+            check_result(algorithm, sorter.as_ref(), input, &array, &context);
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+
+/// Target rank of the selection algorithms, by default: the median.
+pub const DEFAULT_TARGET_PERCENT: usize = 50;
+
+/// Returns the index of the element at `percent`% of the way through `len` sorted elements.
+pub(crate) fn percentile_index(percent: usize, len: usize) -> usize {
+    len.saturating_sub(1) * percent.min(100) / 100
+}
+
+/// Sorts a group of at most five values with insertion sort and returns its median.
+fn median_of_group(group: &[usize], comparisons: &mut usize) -> usize {
+    let mut group = group.to_vec();
+    for i in 1..group.len() {
+        let mut j = i;
+        while j > 0 && {
+            *comparisons += 1;
+            group[j - 1] > group[j]
+        } {
+            group.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+    group[(group.len() - 1) / 2]
+}
+
+/// Returns the `k`-th smallest of `values` with the median-of-medians algorithm,
+/// which takes a linear number of comparisons in the worst case.
+fn select(values: Vec<usize>, k: usize, comparisons: &mut usize) -> usize {
+    if values.len() <= 5 {
+        let mut values = values;
+        values.sort_unstable();
+        *comparisons += values.len() * values.len().saturating_sub(1) / 2;
+        return values[k];
+    }
+
+    // The median of the group medians splits the values between 30% and 70%.
+    let medians: Vec<usize> = values
+        .chunks(5)
+        .map(|group| median_of_group(group, comparisons))
+        .collect();
+    let middle = medians.len() / 2;
+    let pivot = select(medians, middle, comparisons);
+
+    *comparisons += values.len();
+    let smaller: Vec<usize> = values.iter().copied().filter(|&v| v < pivot).collect();
+    let equal = values.iter().filter(|&&v| v == pivot).count();
+    if k < smaller.len() {
+        select(smaller, k, comparisons)
+    } else if k < smaller.len() + equal {
+        pivot
+    } else {
+        let larger = values.into_iter().filter(|&v| v > pivot).collect();
+        select(larger, k - smaller.len() - equal, comparisons)
+    }
+}
+
+/// Represents Quickselect and its introspective variant, Introselect, and their state.
+/// Like QuickSort the range is partitioned around a pivot, one comparison per step,
+/// but only the side holding the target index is partitioned further, so the target
+/// holds its final value long before the rest of the array is sorted.
+/// Introselect picks median-of-three pivots and, once too many partitions fail to
+/// converge, falls back to median-of-medians pivots for a linear worst case.
+/// Both partition Lomuto-style, so runs of equal values still cost quadratic time.
+pub struct QuickSelect {
+    percent: usize,          // Target rank, as a percentage of the array length.
+    introspective: bool,     // Whether this is Introselect.
+    target: usize,           // Index to finalize, resolved at the first step.
+    low: usize,              // First index of the range still holding the target.
+    high: usize,             // Last index of the range still holding the target.
+    scan: Option<usize>,     // Lomuto: the scanning index of the partition in progress.
+    boundary: usize,         // Lomuto: end of the elements `<=` the pivot.
+    partitions: usize,       // Partitions completed so far.
+    depth_limit: usize,      // Partitions allowed before Introselect falls back.
+    fallback: bool,          // Whether median-of-medians pivots are in use.
+    placed: Vec<usize>,      // Positions of pivots, which hold their final values.
+    started: bool,           // Whether the target has been resolved.
+    special: (usize, usize), // Indices touched by the last step.
+    reason: Reasons,         // Reason for the current operation.
+    finished: bool,          // Indicates whether the target is finalized.
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+}
+
+impl QuickSelect {
+    /// Creates a selection of the element at `percent`% of the sorted order.
+    /// # Arguments
+    /// * `percent` - Target rank, 0 for the minimum, 50 for the median, 100 for the maximum.
+    /// * `introspective` - Whether to fall back to median-of-medians pivots (Introselect).
+    pub fn with_options(percent: usize, introspective: bool) -> Self {
+        QuickSelect {
+            percent,
+            introspective,
+            target: 0,
+            low: 0,
+            high: 0,
+            scan: None,
+            boundary: 0,
+            partitions: 0,
+            depth_limit: 0,
+            fallback: false,
+            placed: Vec::new(),
+            started: false,
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+
+    /// Swaps two elements, counting the swap and playing a sound.
+    fn swap(&mut self, array: &mut [usize], a: usize, b: usize) {
+        if a != b {
+            array.swap(a, b);
+            self.swaps += 1;
+            self.writes += 2;
+            play_beep();
+        }
+        self.special = (a, b);
+        self.reason = Reasons::Switching;
+    }
+
+    /// Picks the pivot of `low..=high`: the middle element for Quickselect, the median of three
+    /// for Introselect, or the median of medians once Introselect has fallen back.
+    fn choose_pivot(&mut self, array: &[usize]) -> usize {
+        let (low, high) = (self.low, self.high);
+        let mid = low + (high - low) / 2;
+        if !self.introspective {
+            return mid;
+        }
+        if self.partitions >= self.depth_limit {
+            self.fallback = true;
+        }
+        if self.fallback {
+            let rank = (high - low) / 2;
+            let value = select(array[low..=high].to_vec(), rank, &mut self.comparisons);
+            return (low..=high).find(|&i| array[i] == value).unwrap_or(mid);
+        }
+        self.comparisons += 3;
+        let (a, b, c) = (array[low], array[mid], array[high]);
+        if (a <= b) == (b <= c) {
+            mid
+        } else if (b <= a) == (a <= c) {
+            low
+        } else {
+            high
+        }
+    }
+}
+
+impl Sorter for QuickSelect {
+    /// Creates a Quickselect for the median.
+    fn new() -> Self {
+        Self::with_options(DEFAULT_TARGET_PERCENT, false)
+    }
+
+    /// Returns the indices touched by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step: chooses the pivot of the range holding the target,
+    /// or advances its partition by one comparison.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+        if !self.started {
+            // An empty array has no target to finalize.
+            if array.is_empty() {
+                self.finished = true;
+                return true;
+            }
+            self.started = true;
+            self.target = percentile_index(self.percent, array.len());
+            (self.low, self.high) = (0, array.len() - 1);
+            self.depth_limit = 2 * array.len().ilog2() as usize;
+        }
+
+        let Some(j) = self.scan else {
+            // A range of one element is the target itself.
+            if self.low == self.high {
+                self.placed.push(self.low);
+                self.finished = true;
+                self.special = (usize::MAX, usize::MAX);
+                return true;
+            }
+            let pivot = self.choose_pivot(array);
+            self.swap(array, pivot, self.high);
+            self.boundary = self.low;
+            self.scan = Some(self.low);
+            return false;
+        };
+
+        let (i, high) = (self.boundary, self.high);
+        if j < high {
+            self.comparisons += 1;
+            self.special = (i, j);
+            self.reason = Reasons::Comparing;
+            if array[j] <= array[high] {
+                self.swap(array, i, j);
+                self.boundary += 1;
+            }
+            self.scan = Some(j + 1);
+            return false;
+        }
+
+        // Place the pivot between the two sides; only the side holding the target goes on.
+        self.swap(array, i, high);
+        self.placed.push(i);
+        self.partitions += 1;
+        self.scan = None;
+        if i == self.target {
+            self.finished = true;
+            self.special = (usize::MAX, usize::MAX);
+            return true;
+        } else if self.target < i {
+            self.high = i - 1;
+        } else {
+            self.low = i + 1;
+        }
+        false
+    }
+
+    /// Resets the state for a fresh selection, keeping the target and variant.
+    fn reset_state(&mut self) {
+        *self = Self::with_options(self.percent, self.introspective);
+    }
+
+    /// Checks if the target is finalized.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Returns which pivots Introselect is using.
+    fn phase(&self) -> Option<&'static str> {
+        match (self.introspective, self.fallback, self.finished) {
+            (false, _, _) | (_, _, true) => None,
+            (true, false, false) => Some("Median-of-three pivots"),
+            (true, true, false) => Some("Median-of-medians fallback"),
+        }
+    }
+
+    /// Returns the pivot of the partition in progress.
+    fn pivots(&self) -> Vec<usize> {
+        match self.scan {
+            Some(_) => vec![self.high],
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the target index.
+    fn target(&self) -> Option<(usize, usize)> {
+        self.started.then_some((self.target, self.target + 1))
+    }
+
+    /// Returns the positions of every pivot placed so far.
+    fn finalized(&self) -> Vec<usize> {
+        self.placed.clone()
+    }
+}
@@ -1,65 +1,431 @@
 use super::{Reasons, Sorter};
+use crate::rng;
 use crate::sound::play_beep;
+use rand::Rng;
+use strum_macros::EnumIter;
+
+/// Enum representing how QuickSort picks the pivot of a partition.
+/// For dual-pivot partitioning the strategy is applied to each half of the range.
+#[derive(PartialEq, Debug, EnumIter, Clone, Copy)]
+pub enum PivotStrategy {
+    First,
+    Last,
+    Middle,
+    Random,
+    MedianOfThree,
+    Ninther,
+}
+
+/// Enum representing how QuickSort rearranges a range around its pivot.
+#[derive(PartialEq, Debug, EnumIter, Clone, Copy)]
+pub enum PartitionScheme {
+    Lomuto,
+    Hoare,
+    ThreeWay,
+    DualPivot,
+}
+
+/// State of the partition currently in progress.
+/// All bounds are inclusive, matching the `(low, high)` pairs on the stack.
+enum Partition {
+    /// No partition is running; the next range is taken from the stack.
+    Idle,
+    /// Lomuto: pivot at `high`, `array[low..i]` holds elements `<= pivot`, `j` scans.
+    Lomuto {
+        low: usize,
+        high: usize,
+        i: usize,
+        j: usize,
+    },
+    /// Hoare: pivot value taken from `low`, `i` scans right and `j` scans left.
+    Hoare {
+        low: usize,
+        high: usize,
+        i: usize,
+        j: usize,
+        pivot: usize,
+        scanning_left: bool,
+    },
+    /// Dutch national flag: `array[low..lt]` < pivot, `array[lt..i]` == pivot, `array[gt+1..=high]` > pivot.
+    ThreeWay {
+        low: usize,
+        high: usize,
+        lt: usize,
+        i: usize,
+        gt: usize,
+    },
+    /// Yaroslavskiy: pivots at `low` and `high`, `array[low+1..lt]` < first,
+    /// `array[gt+1..high]` > second, `k` scans the middle.
+    DualPivot {
+        low: usize,
+        high: usize,
+        lt: usize,
+        k: usize,
+        gt: usize,
+    },
+}
 
 /// Represents the QuickSort algorithm and its state.
+/// Every step performs a single comparison (and any resulting swap) of the current partition.
 pub struct QuickSort {
     partition_stack: Vec<(usize, usize)>, // Stack to track the partitions (low, high)
+    partition: Partition,                 // The partition currently being processed.
+    pivot_strategy: PivotStrategy,        // How the pivot of each range is chosen.
+    scheme: PartitionScheme,              // How each range is partitioned.
+    cutoff: usize,                        // Ranges shorter than this are not partitioned.
+    small_ranges: Vec<(usize, usize)>,    // Short ranges `(low, end)` left for another sorter.
+    pivots: Vec<usize>,                   // Indices of the current pivot(s).
+    special: (usize, usize),              // Indices compared or swapped in the last step.
     reason: Reasons, // Reason for the current operation (Comparing or Switching)
-    swaps: usize,    // Indicates if the sorting is finished.
+    started: bool,   // Whether the initial range has been pushed.
+    finished: bool,  // Indicates whether the sorting is complete.
+    swaps: usize,
     comparisons: usize,
+    writes: usize,
 }
 
 impl QuickSort {
-    /// Partitions the array and returns the pivot index.
-    /// This function selects a pivot and places it at the correct sorted position in the array.
-    /// # Arguments
-    /// * `array` - The array slice to partition.
-    /// * `low` - The starting index of the partition.
-    /// * `high` - The ending index of the partition.
-    /// # Returns
-    /// The index of the pivot after partitioning.
-    fn partition(&mut self, array: &mut [usize], low: usize, high: usize) -> usize {
-        let pivot = array[high]; // Choose the pivot element (usually the last element in the range)
-        let mut i = low;
-
-        // Loop over the array, comparing each element with the pivot.
-        for j in low..high {
-            self.reason = Reasons::Comparing; // Indicate comparing step
-            if array[j] <= pivot {
-                array.swap(i, j); // Swap elements that are less than or equal to the pivot
-                i += 1;
+    /// Creates a QuickSort with the given pivot strategy and partition scheme.
+    pub fn with_options(pivot_strategy: PivotStrategy, scheme: PartitionScheme) -> Self {
+        Self::with_cutoff(pivot_strategy, scheme, 2)
+    }
+
+    /// Creates a QuickSort that stops partitioning ranges shorter than `cutoff`.
+    /// Those ranges are left unsorted and collected for another algorithm to finish,
+    /// see `take_small_ranges`.
+    pub fn with_cutoff(
+        pivot_strategy: PivotStrategy,
+        scheme: PartitionScheme,
+        cutoff: usize,
+    ) -> Self {
+        QuickSort {
+            partition_stack: Vec::new(), // Initialize an empty stack to manage partitions
+            partition: Partition::Idle,
+            pivot_strategy,
+            scheme,
+            cutoff: cutoff.max(2),
+            small_ranges: Vec::new(),
+            pivots: Vec::new(),
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing, // The initial reason is "Comparing"
+            started: false,
+            finished: false,
+            swaps: 0,
+            comparisons: 0,
+            writes: 0,
+        }
+    }
+
+    /// Returns the index of the median of the values at `a`, `b` and `c`.
+    fn median_index(&mut self, array: &[usize], a: usize, b: usize, c: usize) -> usize {
+        self.comparisons += 3;
+        if (array[a] <= array[b]) == (array[b] <= array[c]) {
+            b
+        } else if (array[b] <= array[a]) == (array[a] <= array[c]) {
+            a
+        } else {
+            c
+        }
+    }
+
+    /// Picks a pivot index in `low..=high` according to the pivot strategy.
+    fn choose_pivot(&mut self, array: &[usize], low: usize, high: usize) -> usize {
+        let mid = low + (high - low) / 2;
+        match self.pivot_strategy {
+            PivotStrategy::First => low,
+            PivotStrategy::Last => high,
+            PivotStrategy::Middle => mid,
+            PivotStrategy::Random => rng::with_rng(|rng| rng.gen_range(low..=high)),
+            PivotStrategy::MedianOfThree => self.median_index(array, low, mid, high),
+            PivotStrategy::Ninther => {
+                let eighth = (high - low) / 8;
+                if eighth == 0 {
+                    return self.median_index(array, low, mid, high);
+                }
+                // Tukey's ninther: the median of three medians of three.
+                let a = self.median_index(array, low, low + eighth, low + 2 * eighth);
+                let b = self.median_index(array, mid - eighth, mid, mid + eighth);
+                let c = self.median_index(array, high - 2 * eighth, high - eighth, high);
+                self.median_index(array, a, b, c)
             }
         }
+    }
+
+    /// Swaps two elements, counting the swap and playing a sound.
+    fn swap(&mut self, array: &mut [usize], a: usize, b: usize) {
+        if a != b {
+            array.swap(a, b);
+            self.swaps += 1;
+            self.writes += 2;
+            play_beep();
+        }
+        self.special = (a, b);
+        self.reason = Reasons::Switching;
+    }
+
+    /// Pushes the range `low..end` onto the stack if it still needs sorting,
+    /// or sets it aside if it is shorter than the cutoff.
+    fn push(&mut self, low: usize, end: usize) {
+        if end >= low + self.cutoff {
+            self.partition_stack.push((low, end - 1));
+        } else if end > low + 1 {
+            self.small_ranges.push((low, end));
+        }
+    }
 
-        // After processing, swap the pivot to its correct position (i-th index)
-        array.swap(i, high);
-        self.reason = Reasons::Switching; // Indicate switching after pivot placement
-        play_beep();
-        self.swaps += 1;
-        i // Return the pivot index
+    /// Removes and returns the ranges `(low, end)` set aside for being shorter than the cutoff.
+    pub(crate) fn take_small_ranges(&mut self) -> Vec<(usize, usize)> {
+        std::mem::take(&mut self.small_ranges)
+    }
+
+    /// Returns the range `(low, end)` being partitioned, if any.
+    pub(crate) fn range(&self) -> Option<(usize, usize)> {
+        match self.partition {
+            Partition::Idle => None,
+            Partition::Lomuto { low, high, .. }
+            | Partition::Hoare { low, high, .. }
+            | Partition::ThreeWay { low, high, .. }
+            | Partition::DualPivot { low, high, .. } => Some((low, high + 1)),
+        }
+    }
+
+    /// Chooses the pivot(s) for `low..=high` and prepares the partition state.
+    fn start_partition(&mut self, array: &mut [usize], low: usize, high: usize) {
+        self.partition = match self.scheme {
+            PartitionScheme::Lomuto => {
+                let pivot = self.choose_pivot(array, low, high);
+                self.swap(array, pivot, high);
+                self.pivots = vec![high];
+                Partition::Lomuto {
+                    low,
+                    high,
+                    i: low,
+                    j: low,
+                }
+            }
+            PartitionScheme::Hoare => {
+                let pivot = self.choose_pivot(array, low, high);
+                self.swap(array, pivot, low);
+                self.pivots = vec![low];
+                Partition::Hoare {
+                    low,
+                    high,
+                    i: low,
+                    j: high,
+                    pivot: array[low],
+                    scanning_left: true,
+                }
+            }
+            PartitionScheme::ThreeWay => {
+                let pivot = self.choose_pivot(array, low, high);
+                self.swap(array, pivot, low);
+                self.pivots = vec![low];
+                Partition::ThreeWay {
+                    low,
+                    high,
+                    lt: low,
+                    i: low + 1,
+                    gt: high,
+                }
+            }
+            PartitionScheme::DualPivot => {
+                let mid = low + (high - low) / 2;
+                let first = self.choose_pivot(array, low, mid);
+                let second = self.choose_pivot(array, mid + 1, high);
+                self.swap(array, first, low);
+                self.swap(array, second, high);
+                self.comparisons += 1;
+                if array[low] > array[high] {
+                    self.swap(array, low, high);
+                }
+                self.pivots = vec![low, high];
+                Partition::DualPivot {
+                    low,
+                    high,
+                    lt: low + 1,
+                    k: low + 1,
+                    gt: high - 1,
+                }
+            }
+        };
+    }
+
+    /// Advances the current partition by one comparison.
+    fn partition_step(&mut self, array: &mut [usize]) {
+        self.reason = Reasons::Comparing;
+        match self.partition {
+            Partition::Idle => {}
+            Partition::Lomuto {
+                low,
+                high,
+                mut i,
+                mut j,
+            } => {
+                if j < high {
+                    self.comparisons += 1;
+                    self.special = (i, j);
+                    if array[j] <= array[high] {
+                        self.swap(array, i, j);
+                        i += 1;
+                    }
+                    j += 1;
+                    self.partition = Partition::Lomuto { low, high, i, j };
+                } else {
+                    // Place the pivot between the two sides.
+                    self.swap(array, i, high);
+                    self.pivots = vec![i];
+                    self.partition = Partition::Idle;
+                    self.push(low, i);
+                    self.push(i + 1, high + 1);
+                }
+            }
+            Partition::Hoare {
+                low,
+                high,
+                mut i,
+                mut j,
+                pivot,
+                mut scanning_left,
+            } => {
+                self.comparisons += 1;
+                if scanning_left {
+                    self.special = (i, j);
+                    if array[i] < pivot {
+                        i += 1;
+                    } else {
+                        scanning_left = false;
+                    }
+                } else {
+                    self.special = (i, j);
+                    if array[j] > pivot {
+                        j -= 1;
+                    } else if i >= j {
+                        // The scans met: `low..=j` and `j + 1..=high` are the two sides.
+                        self.partition = Partition::Idle;
+                        self.push(low, j + 1);
+                        self.push(j + 1, high + 1);
+                        return;
+                    } else {
+                        self.swap(array, i, j);
+                        // Follow the pivot value if it was moved by the swap.
+                        if self.pivots == [i] {
+                            self.pivots = vec![j];
+                        } else if self.pivots == [j] {
+                            self.pivots = vec![i];
+                        }
+                        i += 1;
+                        j -= 1;
+                        scanning_left = true;
+                    }
+                }
+                self.partition = Partition::Hoare {
+                    low,
+                    high,
+                    i,
+                    j,
+                    pivot,
+                    scanning_left,
+                };
+            }
+            Partition::ThreeWay {
+                low,
+                high,
+                mut lt,
+                mut i,
+                mut gt,
+            } => {
+                if i <= gt {
+                    self.comparisons += 1;
+                    self.special = (i, lt);
+                    if array[i] < array[lt] {
+                        self.swap(array, lt, i);
+                        lt += 1;
+                        i += 1;
+                    } else if array[i] > array[lt] {
+                        self.swap(array, i, gt);
+                        gt -= 1;
+                    } else {
+                        i += 1;
+                    }
+                    self.pivots = vec![lt];
+                    self.partition = Partition::ThreeWay {
+                        low,
+                        high,
+                        lt,
+                        i,
+                        gt,
+                    };
+                } else {
+                    // Everything in `lt..=gt` equals the pivot and is already in place.
+                    self.pivots = (lt..=gt).collect();
+                    self.special = (lt, gt);
+                    self.partition = Partition::Idle;
+                    self.push(low, lt);
+                    self.push(gt + 1, high + 1);
+                }
+            }
+            Partition::DualPivot {
+                low,
+                high,
+                mut lt,
+                mut k,
+                mut gt,
+            } => {
+                if k <= gt {
+                    self.comparisons += 1;
+                    self.special = (k, gt);
+                    if array[k] < array[low] {
+                        self.swap(array, k, lt);
+                        lt += 1;
+                    } else if array[k] > array[high] {
+                        while array[gt] > array[high] && k < gt {
+                            self.comparisons += 1;
+                            gt -= 1;
+                        }
+                        self.swap(array, k, gt);
+                        gt -= 1;
+                        self.comparisons += 1;
+                        if array[k] < array[low] {
+                            self.swap(array, k, lt);
+                            lt += 1;
+                        }
+                    }
+                    k += 1;
+                    self.partition = Partition::DualPivot {
+                        low,
+                        high,
+                        lt,
+                        k,
+                        gt,
+                    };
+                } else {
+                    // Move both pivots into their final positions.
+                    lt -= 1;
+                    gt += 1;
+                    self.swap(array, low, lt);
+                    self.swap(array, high, gt);
+                    self.pivots = vec![lt, gt];
+                    self.partition = Partition::Idle;
+                    self.push(low, lt);
+                    self.push(lt + 1, gt);
+                    self.push(gt + 1, high + 1);
+                }
+            }
+        }
     }
 }
 
 impl Sorter for QuickSort {
-    /// Creates a new instance of QuickSort.
+    /// Creates a new instance of QuickSort with the last element as pivot and Lomuto partitioning.
     fn new() -> Self {
-        QuickSort {
-            partition_stack: Vec::new(), // Initialize an empty stack to manage partitions
-            reason: Reasons::Comparing,  // The initial reason is "Comparing"
-            swaps: 0,                    // Indicates if the sorting is finished.
-            comparisons: 0,
-        }
+        Self::with_options(PivotStrategy::Last, PartitionScheme::Lomuto)
     }
 
-    /// Returns the special indices currently being compared (low, high).
+    /// Returns the special indices currently being compared or swapped.
     /// # Returns
-    /// A tuple `(low, high)` representing the current partition's bounds.
+    /// A tuple of the two indices touched by the last step.
     fn special(&self) -> (usize, usize) {
-        if let Some(&(low, high)) = self.partition_stack.last() {
-            (low, high) // Return the last partition's low and high indices
-        } else {
-            (usize::MAX, usize::MAX) // Return MAX values if no partitions are present
-        }
+        self.special
     }
 
     /// Returns the reason for the current sorting action.
@@ -77,48 +443,46 @@ impl Sorter for QuickSort {
     /// * `true` if sorting is complete.
     /// * `false` if sorting is still in progress.
     fn step(&mut self, array: &mut Vec<usize>) -> bool {
-        // Initialize the stack with the first partition (the entire array)
-        if self.partition_stack.is_empty() {
-            if array.is_empty() {
-                return true; // Handle edge case: empty array
-            }
-            self.partition_stack.push((0, array.len() - 1)); // The initial partition
+        if self.finished {
+            return true;
         }
 
-        // Process the partitions (perform partitioning and recursive sorting)
-        while let Some((low, high)) = self.partition_stack.pop() {
-            if low < high {
-                // Partition the array and get the pivot index
-                let pivot = self.partition(array, low, high);
+        // Initialize the stack with the first partition (the entire array)
+        if !self.started {
+            self.started = true;
+            self.push(0, array.len()); // The initial partition
+        }
 
-                // After partitioning, push the left and right partitions to the stack
-                if pivot > low {
-                    self.partition_stack.push((low, pivot - 1)); // Left partition (elements before the pivot)
+        if let Partition::Idle = self.partition {
+            // Start partitioning the next range, or finish if none are left.
+            match self.partition_stack.pop() {
+                Some((low, high)) => self.start_partition(array, low, high),
+                None => {
+                    self.finished = true;
+                    self.pivots.clear();
+                    self.special = (usize::MAX, usize::MAX);
+                    return true;
                 }
-                if pivot + 1 < high {
-                    self.partition_stack.push((pivot + 1, high)); // Right partition (elements after the pivot)
-                }
-
-                // Continue sorting the left and right parts
-                return false; // Sorting is not complete yet, so return false
             }
+        } else {
+            self.partition_step(array);
         }
 
-        // If no more partitions to process, sorting is complete
-        true
+        false
     }
 
-    /// Resets the state of the QuickSort instance for a fresh sort.
-    /// Clears the stack and resets the reason to "Comparing".
+    /// Resets the state of the QuickSort instance for a fresh sort,
+    /// keeping the selected pivot strategy, partition scheme and cutoff.
     fn reset_state(&mut self) {
-        *self = Self::new(); // Reset all fields to their initial state.
+        *self = Self::with_cutoff(self.pivot_strategy, self.scheme, self.cutoff);
     }
 
     /// Checks if the QuickSort process is finished.
     /// # Returns `true` if sorting is finished, otherwise `false`.
     fn is_finished(&self) -> bool {
-        self.partition_stack.is_empty() // Sorting is finished if the stack is empty
+        self.finished
     }
+
     fn comparisons(&self) -> usize {
         self.comparisons
     }
@@ -126,4 +490,13 @@ impl Sorter for QuickSort {
     fn swaps(&self) -> usize {
         self.swaps
     }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Returns the indices of the current pivot(s).
+    fn pivots(&self) -> Vec<usize> {
+        self.pivots.clone()
+    }
 }
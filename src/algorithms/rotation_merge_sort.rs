@@ -0,0 +1,121 @@
+use super::subrange::RangeRotationMerge;
+use super::{Reasons, Sorter};
+
+/// Represents a bottom-up merge sort that merges in place with rotations.
+/// It follows the same passes as `MergeSort`, but instead of copying through an O(n)
+/// buffer each merge repeatedly rotates a block of the right run in front of the left run,
+/// so it needs no extra memory at the cost of more element moves.
+pub struct RotationMergeSort {
+    width: usize,                      // Length of the runs merged in the current pass.
+    index: usize,                      // Start of the next pair of runs to merge.
+    merge: Option<RangeRotationMerge>, // Merge in progress, if any.
+    special: (usize, usize),           // Indices touched by the last step.
+    reason: Reasons,                   // Reason for the current operation.
+    finished: bool,                    // Indicates whether the sorting is complete.
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+}
+
+impl Sorter for RotationMergeSort {
+    /// Creates a new instance of RotationMergeSort.
+    fn new() -> Self {
+        RotationMergeSort {
+            width: 1,
+            index: 0,
+            merge: None,
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+
+    /// Returns the indices touched by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step: one rotation of the current merge,
+    /// or the check that starts the merge of the next pair of runs.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+        let n = array.len();
+
+        if let Some(merge) = &mut self.merge {
+            let swaps = self.swaps;
+            let done = merge.step(
+                array,
+                &mut self.comparisons,
+                &mut self.swaps,
+                &mut self.writes,
+            );
+            self.special = merge.focus();
+            self.reason = if self.swaps > swaps {
+                Reasons::Switching
+            } else {
+                Reasons::Comparing
+            };
+            if done {
+                self.merge = None;
+            }
+            return false;
+        }
+
+        // Move on to the next pass once no pair of runs is left in this one.
+        if self.index + self.width >= n {
+            self.width *= 2;
+            self.index = 0;
+            if self.width >= n {
+                self.finished = true;
+                self.special = (usize::MAX, usize::MAX);
+                return true;
+            }
+        }
+
+        let start = self.index;
+        let mid = start + self.width;
+        let end = (mid + self.width).min(n);
+        self.index = end;
+
+        // Runs that are already in order need no merge.
+        self.comparisons += 1;
+        self.special = (mid - 1, mid);
+        self.reason = Reasons::Comparing;
+        if array[mid - 1] > array[mid] {
+            self.merge = Some(RangeRotationMerge::new(start, mid, end));
+        }
+        false
+    }
+
+    /// Resets the state of the RotationMergeSort instance for a fresh sort.
+    fn reset_state(&mut self) {
+        *self = Self::new(); // Reset all fields to their initial state.
+    }
+
+    /// Checks if the sorting process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+}
@@ -8,6 +8,7 @@ pub struct SelectionSort {
     reason: Reasons, // Tracks the reason for the current action, either comparing or switching.
     is_sorted: bool, // Tracks whether the sorting is complete.
     comparisons: usize, // Indicates if the sorting is finished.
+    writes: usize,
     swaps: usize,
 }
 
@@ -20,6 +21,7 @@ impl Sorter for SelectionSort {
             reason: Reasons::Comparing, // Initially, the action is comparing.
             is_sorted: false, // Initially, the sorting is not complete.
             comparisons: 0,
+            writes: 0,
             swaps: 0,
         }
     }
@@ -28,6 +30,9 @@ impl Sorter for SelectionSort {
     /// # Returns
     /// A tuple `(current_index, min_index)` representing the indices involved in the operation.
     fn special(&self) -> (usize, usize) {
+        if self.is_sorted {
+            return (usize::MAX, usize::MAX); // Nothing is compared once sorted.
+        }
         (self.current_index, self.min_index) // These are the indices of the current element and the minimum element.
     }
 
@@ -44,8 +49,8 @@ impl Sorter for SelectionSort {
     /// * `true` if sorting is complete.
     /// * `false` if sorting is still in progress.
     fn step(&mut self, array: &mut Vec<usize>) -> bool {
-        // If the current index is beyond the last element, the sorting is complete.
-        if self.current_index >= array.len() {
+        // If the current index is the last element, it is already in place and the sorting is complete.
+        if self.current_index + 1 >= array.len() {
             self.is_sorted = true; // Mark the sorting as complete.
             return true; // Indicate that sorting is complete.
         }
@@ -65,6 +70,9 @@ impl Sorter for SelectionSort {
 
         // Swap the current element with the smallest element found in the remaining portion.
         array.swap(self.current_index, self.min_index);
+        if self.current_index != self.min_index {
+            self.writes += 2;
+        }
 
         // Move to the next element in the array for the next iteration.
         self.current_index += 1;
@@ -94,4 +102,8 @@ impl Sorter for SelectionSort {
     fn swaps(&self) -> usize {
         self.swaps
     }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
 }
@@ -1,24 +1,118 @@
 use super::{Reasons, Sorter};
 use crate::sound::play_beep;
+use strum_macros::EnumIter;
+
+/// The gap sequences ShellSort can use, from the largest gap down to 1.
+#[derive(PartialEq, Debug, EnumIter, Clone, Copy)]
+pub enum GapSequence {
+    /// Shell's original sequence: n/2, n/4, ..., 1.
+    Shell,
+    /// Knuth's (3^k - 1) / 2: 1, 4, 13, 40, 121, ...
+    Knuth,
+    /// Sedgewick's 4^k + 3 * 2^(k-1) + 1: 1, 8, 23, 77, 281, ...
+    Sedgewick,
+    /// Tokuda's ceil((9^k - 4^k) / (5 * 4^(k-1))): 1, 4, 9, 20, 46, 103, ...
+    Tokuda,
+    /// Ciura's empirical 1, 4, 10, 23, 57, 132, 301, 701, 1750, extended by a factor of 2.25.
+    Ciura,
+    /// Pratt's 3-smooth numbers 2^p * 3^q: 1, 2, 3, 4, 6, 8, 9, 12, ...
+    Pratt,
+}
+
+impl GapSequence {
+    /// Returns the gaps below `n` in decreasing order, always ending with 1.
+    /// # Arguments
+    /// * `n` - The length of the array to sort.
+    pub fn gaps(self, n: usize) -> Vec<usize> {
+        let mut gaps: Vec<usize> = match self {
+            GapSequence::Shell => std::iter::successors(Some(n / 2), |&gap| Some(gap / 2))
+                .take_while(|&gap| gap > 0)
+                .collect(),
+            GapSequence::Knuth => std::iter::successors(Some(1), |&gap| Some(3 * gap + 1))
+                .take_while(|&gap| gap < n)
+                .collect(),
+            GapSequence::Sedgewick => std::iter::once(1)
+                .chain((1..).map(|k: u32| 4usize.pow(k) + 3 * 2usize.pow(k - 1) + 1))
+                .take_while(|&gap| gap < n)
+                .collect(),
+            GapSequence::Tokuda => (1..)
+                .map(|k: i32| ((9.0 * 2.25f64.powi(k - 1) - 4.0) / 5.0).ceil() as usize)
+                .take_while(|&gap| gap < n)
+                .collect(),
+            GapSequence::Ciura => [1, 4, 10, 23, 57, 132, 301, 701, 1750]
+                .into_iter()
+                .chain(std::iter::successors(Some(3937), |&gap| {
+                    Some((gap as f64 * 2.25) as usize)
+                }))
+                .take_while(|&gap| gap < n)
+                .collect(),
+            GapSequence::Pratt => {
+                let mut gaps = Vec::new();
+                let mut power_of_two = 1;
+                while power_of_two < n {
+                    let mut gap = power_of_two;
+                    while gap < n {
+                        gaps.push(gap);
+                        gap *= 3;
+                    }
+                    power_of_two *= 2;
+                }
+                gaps
+            }
+        };
+        gaps.sort_unstable();
+        gaps.dedup();
+        if gaps.first() != Some(&1) {
+            gaps.insert(0, 1);
+        }
+        gaps.reverse();
+        gaps
+    }
+}
 
 pub struct ShellSort {
+    sequence: GapSequence, // The gap sequence in use.
+    gaps: Vec<usize>,      // Gaps still to use, the next one last.
     gap: usize,
     i: usize,
+    len: usize, // Length of the array, known after the first step.
     finished: bool,
     swaps: usize, // Indicates if the sorting is finished.
     comparisons: usize,
+    writes: usize,
 }
 
 impl ShellSort {
     pub fn new() -> Self {
+        Self::with_sequence(GapSequence::Shell)
+    }
+
+    /// Creates a ShellSort that uses the given gap sequence.
+    pub fn with_sequence(sequence: GapSequence) -> Self {
         Self {
+            sequence,
+            gaps: Vec::new(),
             gap: 0,
             i: 0,
+            len: 0,
             finished: false, // Sorting is not finished initially.
             comparisons: 0,
+            writes: 0,
             swaps: 0,
         }
     }
+
+    /// Moves on to the next gap of the sequence, or 0 once every gap has been used.
+    fn next_gap(&mut self) {
+        self.gap = self.gaps.pop().unwrap_or(0);
+        self.i = self.gap;
+    }
+}
+
+impl Default for ShellSort {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Sorter for ShellSort {
@@ -30,7 +124,12 @@ impl Sorter for ShellSort {
         if self.finished {
             (usize::MAX, usize::MAX)
         } else {
-            (self.i, self.i + self.gap.min(1)) // Highlights elements being compared
+            // Highlights the element being inserted and the one a gap before it.
+            if self.gap == 0 || self.i >= self.len {
+                (usize::MAX, usize::MAX)
+            } else {
+                (self.i, self.i.saturating_sub(self.gap))
+            }
         }
     }
 
@@ -47,16 +146,17 @@ impl Sorter for ShellSort {
             return true;
         }
 
-        // Initialize gap on first run
+        // Initialize the gaps on first run
         if self.gap == 0 {
-            self.gap = array.len() / 2;
-            self.i = self.gap;
+            self.len = array.len();
+            self.gaps = self.sequence.gaps(array.len());
+            self.gaps.reverse();
+            self.next_gap();
         }
 
         if self.gap > 0 {
             if self.i < array.len() {
-                play_beep();
-                self.swaps += 1; // Play sound for visualization
+                play_beep(); // Play sound for visualization
 
                 let temp = array[self.i];
                 let mut j = self.i;
@@ -65,33 +165,38 @@ impl Sorter for ShellSort {
                 while j >= self.gap && array[j - self.gap] > temp {
                     self.comparisons += 1;
                     array[j] = array[j - self.gap];
+                    self.writes += 1;
                     j -= self.gap;
 
                     play_beep();
                     self.swaps += 1;
                 }
                 self.comparisons += 1;
-                array[j] = temp;
+                if j != self.i {
+                    array[j] = temp;
+                    self.writes += 1;
+                }
                 self.i += 1; // Move to next element
             } else {
                 // Reduce gap after finishing a full pass
-                self.gap /= 2;
-                self.i = self.gap;
+                self.next_gap();
 
                 // If gap reaches 0, sorting is finished
                 if self.gap == 0 {
                     self.finished = true;
+                    return true;
                 }
             }
         } else {
             self.finished = true;
+            return true;
         }
 
         false
     }
 
     fn reset_state(&mut self) {
-        *self = Self::new(); // Reset all fields to their initial state.
+        *self = Self::with_sequence(self.sequence); // Keep the gap sequence.
     }
 
     fn is_finished(&self) -> bool {
@@ -104,4 +209,21 @@ impl Sorter for ShellSort {
     fn swaps(&self) -> usize {
         self.swaps
     }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Links the elements of the gap chain holding the current element,
+    /// which form the interleaved subarray being insertion sorted.
+    fn links(&self) -> Vec<(usize, usize)> {
+        if self.finished || self.gap == 0 {
+            return Vec::new();
+        }
+        let first = self.i.min(self.len.saturating_sub(1)) % self.gap;
+        (first..self.len.saturating_sub(self.gap))
+            .step_by(self.gap)
+            .map(|index| (index, index + self.gap))
+            .collect()
+    }
 }
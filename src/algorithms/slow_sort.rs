@@ -0,0 +1,149 @@
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+
+/// A recursive call of SlowSort on `low..=high` and how far it has progressed.
+struct Call {
+    low: usize,  // First index of the range.
+    high: usize, // Last index of the range.
+    stage: u8,   // 0 before the first sub-call, 2 once both halves are sorted.
+}
+
+/// Represents SlowSort, the "multiply and surrender" algorithm of Broder and Stolfi.
+/// Both halves are sorted recursively, the larger of their maxima is moved to the end,
+/// and the rest is sorted again recursively, for a superpolynomial number of comparisons.
+/// The recursion runs on an explicit stack, one comparison per step.
+pub struct SlowSort {
+    stack: Vec<Call>,        // Calls still in progress, the innermost last.
+    started: bool,           // Whether the call for the whole array has been pushed.
+    special: (usize, usize), // Indices compared by the last step.
+    reason: Reasons,         // Reason for the current operation.
+    finished: bool,          // Indicates whether the sorting is complete.
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+}
+
+impl Sorter for SlowSort {
+    /// Creates a new instance of SlowSort.
+    fn new() -> Self {
+        SlowSort {
+            stack: Vec::new(),
+            started: false,
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+
+    /// Returns the indices compared by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step: compares the maxima of the two sorted halves of the
+    /// innermost call and moves the larger one to its end.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+        if !self.started {
+            self.started = true;
+            self.stack.push(Call {
+                low: 0,
+                high: array.len().saturating_sub(1),
+                stage: 0,
+            });
+        }
+
+        while let Some(call) = self.stack.last_mut() {
+            let (low, high) = (call.low, call.high);
+            if low >= high {
+                self.stack.pop();
+                continue;
+            }
+            let mid = low + (high - low) / 2;
+            match call.stage {
+                0 => {
+                    call.stage = 1;
+                    self.stack.push(Call {
+                        low,
+                        high: mid,
+                        stage: 0,
+                    });
+                }
+                1 => {
+                    call.stage = 2;
+                    self.stack.push(Call {
+                        low: mid + 1,
+                        high,
+                        stage: 0,
+                    });
+                }
+                _ => {
+                    // The rest is sorted by the same call on a shorter range.
+                    *call = Call {
+                        low,
+                        high: high - 1,
+                        stage: 0,
+                    };
+                    self.comparisons += 1;
+                    self.special = (mid, high);
+                    self.reason = Reasons::Comparing;
+                    if array[high] < array[mid] {
+                        array.swap(mid, high);
+                        self.swaps += 1;
+                        self.writes += 2;
+                        self.reason = Reasons::Switching;
+                        play_beep();
+                    }
+                    return false;
+                }
+            }
+        }
+
+        self.finished = true;
+        self.special = (usize::MAX, usize::MAX);
+        true
+    }
+
+    /// Resets the state of the SlowSort instance for a fresh sort.
+    fn reset_state(&mut self) {
+        *self = Self::new(); // Reset all fields to their initial state.
+    }
+
+    /// Checks if the sorting process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// The call tree does not depend on the values, so the count is exact:
+    /// C(n) = C(⌈n/2⌉) + C(⌊n/2⌋) + 1 + C(n - 1), with C(1) = 0.
+    fn estimated_steps(&self, len: usize) -> Option<f64> {
+        let mut comparisons = vec![0.0; len + 1];
+        for n in 2..=len {
+            comparisons[n] =
+                comparisons[n.div_ceil(2)] + comparisons[n / 2] + 1.0 + comparisons[n - 1];
+        }
+        Some(comparisons[len] + 1.0)
+    }
+}
@@ -0,0 +1,263 @@
+use super::structure::Structure;
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+use std::borrow::Cow;
+
+/// Returns the `order`-th Leonardo number: 1, 1, 3, 5, 9, 15, 25, ...
+pub fn leonardo(order: usize) -> usize {
+    let (mut a, mut b) = (1, 1);
+    for _ in 0..order {
+        (a, b) = (b, a + b + 1);
+    }
+    a
+}
+
+/// Returns the roots and orders of the two subtrees of a Leonardo heap,
+/// as `((left_root, left_order), (right_root, right_order))`.
+/// Heaps of order 0 and 1 are single nodes and have no children.
+pub fn leonardo_children(root: usize, order: usize) -> Option<((usize, usize), (usize, usize))> {
+    if order < 2 {
+        return None;
+    }
+    let right = root - 1;
+    let left = right - leonardo(order - 2);
+    Some(((left, order - 1), (right, order - 2)))
+}
+
+/// The operation currently in progress.
+enum Operation {
+    /// Waiting for the next heap to rectify or the next element to add or remove.
+    Idle,
+    /// Moving the root of `heaps[index]` left past larger roots.
+    Rectify { index: usize },
+    /// Sifting `root` down into its Leonardo heap of the given order.
+    Sift { root: usize, order: usize },
+}
+
+/// Represents the SmoothSort algorithm and its state.
+/// SmoothSort keeps the unsorted prefix as a forest of Leonardo heaps whose roots
+/// increase from left to right, so nearly sorted input needs close to linear work.
+/// Each step performs one comparison (and any resulting swap).
+pub struct SmoothSort {
+    heaps: Vec<(usize, usize)>, // The forest as (root index, order), leftmost heap first.
+    pending: Vec<usize>,        // Heaps waiting to be rectified; the last one is handled first.
+    operation: Operation,       // The operation in progress.
+    next: usize,                // Next element to add to the forest while building.
+    len: usize,                 // Length of the array, known after the first step.
+    special: (usize, usize),    // Indices compared or swapped in the last step.
+    reason: Reasons,            // Reason for the current operation.
+    finished: bool,             // Indicates whether the sorting is complete.
+    comparisons: usize,
+    writes: usize,
+    swaps: usize,
+}
+
+impl SmoothSort {
+    /// Adds the element at `index` to the forest, merging the two rightmost heaps if possible.
+    fn add(&mut self, index: usize) {
+        let count = self.heaps.len();
+        if count >= 2 && self.heaps[count - 2].1 == self.heaps[count - 1].1 + 1 {
+            let order = self.heaps[count - 2].1 + 1;
+            self.heaps.truncate(count - 2);
+            self.heaps.push((index, order));
+        } else if count >= 1 && self.heaps[count - 1].1 == 1 {
+            self.heaps.push((index, 0));
+        } else {
+            self.heaps.push((index, 1));
+        }
+        self.pending.push(self.heaps.len() - 1);
+    }
+
+    /// Removes the rightmost root, which is the largest remaining element,
+    /// and exposes its two subtrees as separate heaps.
+    fn remove(&mut self) {
+        let Some((root, order)) = self.heaps.pop() else {
+            return;
+        };
+        if let Some((left, right)) = leonardo_children(root, order) {
+            self.heaps.push(left);
+            self.heaps.push(right);
+            // Rectify the left subtree first, then the right one.
+            self.pending.push(self.heaps.len() - 1);
+            self.pending.push(self.heaps.len() - 2);
+        }
+    }
+
+    /// Swaps two elements, counting the swap and playing a sound.
+    fn swap(&mut self, array: &mut [usize], a: usize, b: usize) {
+        array.swap(a, b);
+        self.swaps += 1;
+        self.writes += 2;
+        self.reason = Reasons::Switching;
+        play_beep();
+    }
+
+    /// Performs one comparison of the rectify pass on `heaps[index]`.
+    fn rectify_step(&mut self, array: &mut [usize], index: usize) {
+        let (root, order) = self.heaps[index];
+        if index == 0 {
+            self.operation = Operation::Sift { root, order };
+            return;
+        }
+
+        let previous = self.heaps[index - 1].0;
+        self.special = (previous, root);
+        self.reason = Reasons::Comparing;
+
+        // The previous root may only move right if it exceeds this root and both its children.
+        let mut largest = array[root];
+        if let Some(((left, _), (right, _))) = leonardo_children(root, order) {
+            self.comparisons += 1;
+            largest = largest.max(array[left]).max(array[right]);
+        }
+        self.comparisons += 1;
+
+        if array[previous] > largest {
+            self.swap(array, previous, root);
+            self.operation = Operation::Rectify { index: index - 1 };
+        } else {
+            self.operation = Operation::Sift { root, order };
+        }
+    }
+
+    /// Performs one level of sift-down from `root` into its Leonardo heap.
+    fn sift_step(&mut self, array: &mut [usize], root: usize, order: usize) {
+        let Some(((left, left_order), (right, right_order))) = leonardo_children(root, order)
+        else {
+            self.operation = Operation::Idle;
+            return;
+        };
+
+        self.comparisons += 2;
+        let (child, child_order) = if array[left] > array[right] {
+            (left, left_order)
+        } else {
+            (right, right_order)
+        };
+        self.special = (root, child);
+        self.reason = Reasons::Comparing;
+
+        if array[root] < array[child] {
+            self.swap(array, root, child);
+            self.operation = Operation::Sift {
+                root: child,
+                order: child_order,
+            };
+        } else {
+            self.operation = Operation::Idle;
+        }
+    }
+}
+
+impl Sorter for SmoothSort {
+    /// Creates a new instance of SmoothSort.
+    fn new() -> Self {
+        SmoothSort {
+            heaps: Vec::new(),
+            pending: Vec::new(),
+            operation: Operation::Idle,
+            next: 0,
+            len: 0,
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            writes: 0,
+            swaps: 0,
+        }
+    }
+
+    /// Returns the indices compared or swapped in the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step of SmoothSort: one comparison while rectifying or sifting.
+    /// Adding and removing roots does not compare anything, so it happens within the same step.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+        self.len = array.len();
+
+        loop {
+            match self.operation {
+                Operation::Rectify { index } => {
+                    self.rectify_step(array, index);
+                    // The leftmost heap has no neighbour to compare with.
+                    if index > 0 {
+                        return false;
+                    }
+                }
+                Operation::Sift { root, order } => {
+                    self.sift_step(array, root, order);
+                    if leonardo_children(root, order).is_some() {
+                        return false;
+                    }
+                }
+                Operation::Idle => {
+                    if let Some(index) = self.pending.pop() {
+                        self.operation = Operation::Rectify { index };
+                    } else if self.next < array.len() {
+                        // Building: grow the forest by one element.
+                        self.add(self.next);
+                        self.next += 1;
+                    } else if !self.heaps.is_empty() {
+                        // Dequeuing: the rightmost root is the maximum and is already in place.
+                        self.remove();
+                    } else {
+                        self.finished = true;
+                        self.special = (usize::MAX, usize::MAX);
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Resets the state of the SmoothSort instance for a fresh sort.
+    fn reset_state(&mut self) {
+        *self = Self::new(); // Reset all fields to their initial state.
+    }
+
+    /// Checks if the sorting process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Returns the current forest of Leonardo heaps.
+    fn structure(&self) -> Option<Structure<'_>> {
+        if self.len == 0 {
+            return None;
+        }
+        // While building, nothing is in its final place yet.
+        let sorted_from = if self.next < self.len {
+            self.len
+        } else {
+            self.heaps.last().map_or(0, |&(root, _)| root + 1)
+        };
+        Some(Structure::LeonardoForest {
+            len: self.len,
+            heaps: Cow::Borrowed(&self.heaps),
+            sorted_from,
+            focus: self.special,
+        })
+    }
+}
@@ -0,0 +1,139 @@
+use super::Reasons;
+use crate::sound::play_beep;
+
+/// A layer of comparators that touch disjoint wires and can run simultaneously.
+/// Each comparator `(i, j)` has `i < j` and moves the smaller value to `i`.
+pub(crate) type Layer = Vec<(usize, usize)>;
+
+/// Builds the layers of a bitonic sorting network for `n` wires.
+/// The network is built for the next power of two and comparators touching the
+/// padding wires are dropped, which is equivalent to padding with `+inf`.
+pub(crate) fn bitonic_layers(n: usize) -> Vec<Layer> {
+    let size = n.next_power_of_two();
+    let mut layers = Vec::new();
+
+    let mut block = 2;
+    while block <= size {
+        // Compare each element with its mirror in the block, then half-clean.
+        layers.push(layer_from(size, n, |i| i ^ (block - 1)));
+        let mut distance = block / 4;
+        while distance > 0 {
+            layers.push(layer_from(size, n, |i| i ^ distance));
+            distance /= 2;
+        }
+        block *= 2;
+    }
+
+    layers.retain(|layer| !layer.is_empty());
+    layers
+}
+
+/// Builds the layers of Batcher's odd-even merge sorting network for `n` wires.
+pub(crate) fn odd_even_merge_layers(n: usize) -> Vec<Layer> {
+    let size = n.next_power_of_two();
+    let mut layers = Vec::new();
+
+    let mut p = 1;
+    while p < size {
+        let mut k = p;
+        while k > 0 {
+            let mut layer = Vec::new();
+            let mut j = k % p;
+            while j + k < size {
+                for i in 0..k.min(size - j - k) {
+                    // Only merge elements that belong to the same block of size 2p.
+                    if (i + j) / (2 * p) == (i + j + k) / (2 * p) && i + j + k < n {
+                        layer.push((i + j, i + j + k));
+                    }
+                }
+                j += 2 * k;
+            }
+            if !layer.is_empty() {
+                layers.push(layer);
+            }
+            k /= 2;
+        }
+        p *= 2;
+    }
+
+    layers
+}
+
+/// Collects the comparators `(i, partner(i))` with `i < partner(i) < n`.
+fn layer_from(size: usize, n: usize, partner: impl Fn(usize) -> usize) -> Layer {
+    (0..size)
+        .map(|i| (i, partner(i)))
+        .filter(|&(i, j)| i < j && j < n)
+        .collect()
+}
+
+/// Executes a sorting network one layer per step.
+pub(crate) struct NetworkRunner {
+    layers: Vec<Layer>,                // Comparator layers of the network.
+    next: usize,                       // Index of the next layer to execute.
+    highlights: Vec<(usize, Reasons)>, // Wires touched by the last layer.
+}
+
+impl NetworkRunner {
+    /// Creates a runner for the given comparator layers.
+    pub(crate) fn new(layers: Vec<Layer>) -> Self {
+        NetworkRunner {
+            layers,
+            next: 0,
+            highlights: Vec::new(),
+        }
+    }
+
+    /// Executes the next layer of comparators.
+    /// # Returns
+    /// `true` once every layer has been executed.
+    pub(crate) fn step(
+        &mut self,
+        array: &mut [usize],
+        comparisons: &mut usize,
+        swaps: &mut usize,
+        writes: &mut usize,
+    ) -> bool {
+        self.highlights.clear();
+        let Some(layer) = self.layers.get(self.next) else {
+            return true;
+        };
+
+        let mut swapped = false;
+        for &(i, j) in layer {
+            *comparisons += 1;
+            let reason = if array[i] > array[j] {
+                array.swap(i, j);
+                *swaps += 1;
+                *writes += 2;
+                swapped = true;
+                Reasons::Switching
+            } else {
+                Reasons::Comparing
+            };
+            self.highlights.push((i, reason));
+            self.highlights.push((j, reason));
+        }
+        if swapped {
+            play_beep();
+        }
+
+        self.next += 1;
+        false
+    }
+
+    /// Returns the comparator layers of the network.
+    pub(crate) fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Returns the index of the layer executed by the last step, if any.
+    pub(crate) fn current(&self) -> Option<usize> {
+        self.next.checked_sub(1)
+    }
+
+    /// Returns the wires touched by the last layer.
+    pub(crate) fn highlights(&self) -> &[(usize, Reasons)] {
+        &self.highlights
+    }
+}
@@ -0,0 +1,149 @@
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+
+/// A recursive call of StoogeSort on `low..=high` and how far it has progressed.
+struct Call {
+    low: usize,  // First index of the range.
+    high: usize, // Last index of the range.
+    stage: u8,   // 0 before the comparison, then the number of sub-calls made so far plus one.
+}
+
+/// Represents the StoogeSort algorithm and its state.
+/// The first and last elements are swapped if out of order, then the first two thirds,
+/// the last two thirds and the first two thirds again are sorted recursively,
+/// for O(n^2.71) comparisons. The recursion runs on an explicit stack,
+/// one comparison per step.
+pub struct StoogeSort {
+    stack: Vec<Call>,        // Calls still in progress, the innermost last.
+    started: bool,           // Whether the call for the whole array has been pushed.
+    special: (usize, usize), // Indices compared by the last step.
+    reason: Reasons,         // Reason for the current operation.
+    finished: bool,          // Indicates whether the sorting is complete.
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+}
+
+impl Sorter for StoogeSort {
+    /// Creates a new instance of StoogeSort.
+    fn new() -> Self {
+        StoogeSort {
+            stack: Vec::new(),
+            started: false,
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+
+    /// Returns the indices compared by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step: compares (and possibly swaps) the ends of the innermost call.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+        if !self.started {
+            self.started = true;
+            if array.len() > 1 {
+                self.stack.push(Call {
+                    low: 0,
+                    high: array.len() - 1,
+                    stage: 0,
+                });
+            }
+        }
+
+        while let Some(call) = self.stack.last_mut() {
+            let (low, high) = (call.low, call.high);
+            let third = (high - low + 1) / 3;
+            call.stage += 1;
+            match call.stage {
+                1 => {
+                    self.comparisons += 1;
+                    self.special = (low, high);
+                    self.reason = Reasons::Comparing;
+                    if array[low] > array[high] {
+                        array.swap(low, high);
+                        self.swaps += 1;
+                        self.writes += 2;
+                        self.reason = Reasons::Switching;
+                        play_beep();
+                    }
+                    // Ranges of two elements are sorted by the comparison alone.
+                    if third == 0 {
+                        self.stack.pop();
+                    }
+                    return false;
+                }
+                2 | 4 => self.stack.push(Call {
+                    low,
+                    high: high - third,
+                    stage: 0,
+                }),
+                3 => self.stack.push(Call {
+                    low: low + third,
+                    high,
+                    stage: 0,
+                }),
+                _ => {
+                    self.stack.pop();
+                }
+            }
+        }
+
+        self.finished = true;
+        self.special = (usize::MAX, usize::MAX);
+        true
+    }
+
+    /// Resets the state of the StoogeSort instance for a fresh sort.
+    fn reset_state(&mut self) {
+        *self = Self::new(); // Reset all fields to their initial state.
+    }
+
+    /// Checks if the sorting process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Every call makes one comparison and the call tree does not depend on the values,
+    /// so the count is exact: C(n) = 1 + 3·C(n - ⌊n/3⌋), with C(2) = 1.
+    fn estimated_steps(&self, len: usize) -> Option<f64> {
+        let mut steps = 0.0;
+        let mut calls = 1.0;
+        let mut n = len;
+        while n >= 2 {
+            steps += calls;
+            if n < 3 {
+                break;
+            }
+            calls *= 3.0;
+            n -= n / 3;
+        }
+        Some(steps + 1.0)
+    }
+}
@@ -0,0 +1,125 @@
+use super::top_down_merge_sort::Frame;
+use super::tree_sort::Node;
+use std::borrow::Cow;
+
+/// An auxiliary data structure exposed by a sorter, drawn in a side panel next to the bars.
+/// Sorters lend their own data, which `into_owned` copies to keep it past their next step.
+pub enum Structure<'a> {
+    /// A sorting network drawn as a Knuth-style diagram.
+    /// * `wires` - The number of wires, one per array element.
+    /// * `layers` - The comparator layers, each comparator `(i, j)` moving the smaller value to `i`.
+    /// * `current` - The layer executed by the last step, if any.
+    Network {
+        wires: usize,
+        layers: Cow<'a, [Vec<(usize, usize)>]>,
+        current: Option<usize>,
+    },
+    /// The forest of Leonardo heaps maintained by SmoothSort.
+    /// * `len` - The length of the array.
+    /// * `heaps` - The heaps as `(root index, order)`, leftmost first.
+    /// * `sorted_from` - The first index of the sorted suffix.
+    /// * `focus` - The indices compared or swapped in the last step.
+    LeonardoForest {
+        len: usize,
+        heaps: Cow<'a, [(usize, usize)]>,
+        sorted_from: usize,
+        focus: (usize, usize),
+    },
+    /// The buckets filled by BucketSort and sample sort.
+    /// * `buckets` - The values scattered into each bucket.
+    /// * `lower_bounds` - The smallest value accepted by each bucket.
+    /// * `current` - The bucket being gathered or sorted, if any.
+    Buckets {
+        buckets: Cow<'a, [Vec<usize>]>,
+        lower_bounds: Cow<'a, [usize]>,
+        current: Option<usize>,
+    },
+    /// The binary search tree built by TreeSort.
+    /// * `nodes` - The nodes in insertion order, with the root first.
+    /// * `focus` - The node compared with or written by the last step, if any.
+    SearchTree {
+        nodes: Cow<'a, [Node]>,
+        focus: Option<usize>,
+    },
+    /// The piles dealt by PatienceSort.
+    /// * `piles` - The cards of each pile, with the top card last.
+    /// * `focus` - The pile touched by the last step, if any.
+    /// * `dealing` - Whether cards are still being dealt rather than merged.
+    Piles {
+        piles: Cow<'a, [Vec<usize>]>,
+        focus: Option<usize>,
+        dealing: bool,
+    },
+    /// The recursion tree of the top-down MergeSort.
+    /// * `len` - The length of the array.
+    /// * `frames` - Every recursive call created so far.
+    /// * `current` - The frame handled by the last step, if any.
+    /// * `stack_depth` - The number of frames still on the explicit stack.
+    RecursionTree {
+        len: usize,
+        frames: Cow<'a, [Frame]>,
+        current: Option<usize>,
+        stack_depth: usize,
+    },
+}
+
+impl Structure<'_> {
+    /// Copies the borrowed data, so the structure outlives the sorter it came from.
+    pub fn into_owned(self) -> Structure<'static> {
+        match self {
+            Structure::Network {
+                wires,
+                layers,
+                current,
+            } => Structure::Network {
+                wires,
+                layers: Cow::Owned(layers.into_owned()),
+                current,
+            },
+            Structure::LeonardoForest {
+                len,
+                heaps,
+                sorted_from,
+                focus,
+            } => Structure::LeonardoForest {
+                len,
+                heaps: Cow::Owned(heaps.into_owned()),
+                sorted_from,
+                focus,
+            },
+            Structure::Buckets {
+                buckets,
+                lower_bounds,
+                current,
+            } => Structure::Buckets {
+                buckets: Cow::Owned(buckets.into_owned()),
+                lower_bounds: Cow::Owned(lower_bounds.into_owned()),
+                current,
+            },
+            Structure::SearchTree { nodes, focus } => Structure::SearchTree {
+                nodes: Cow::Owned(nodes.into_owned()),
+                focus,
+            },
+            Structure::Piles {
+                piles,
+                focus,
+                dealing,
+            } => Structure::Piles {
+                piles: Cow::Owned(piles.into_owned()),
+                focus,
+                dealing,
+            },
+            Structure::RecursionTree {
+                len,
+                frames,
+                current,
+                stack_depth,
+            } => Structure::RecursionTree {
+                len,
+                frames: Cow::Owned(frames.into_owned()),
+                current,
+                stack_depth,
+            },
+        }
+    }
+}
@@ -0,0 +1,398 @@
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+
+/// Stepwise insertion sort over the subrange `low..high` of an array.
+/// Each step inserts one element into the sorted prefix of the subrange.
+pub(crate) struct RangeInsertion {
+    low: usize,            // First index of the subrange.
+    high: usize,           // One past the last index of the subrange.
+    next: usize,           // Index of the next element to insert.
+    focus: (usize, usize), // Indices touched by the last step.
+}
+
+impl RangeInsertion {
+    /// Creates an insertion sort over `low..high`.
+    pub(crate) fn new(low: usize, high: usize) -> Self {
+        RangeInsertion {
+            low,
+            high,
+            next: low + 1,
+            focus: (usize::MAX, usize::MAX),
+        }
+    }
+
+    /// Inserts the next element of the subrange into its sorted prefix.
+    /// # Returns
+    /// `true` once the whole subrange is sorted.
+    pub(crate) fn step(
+        &mut self,
+        array: &mut [usize],
+        comparisons: &mut usize,
+        swaps: &mut usize,
+        writes: &mut usize,
+    ) -> bool {
+        if self.next >= self.high {
+            return true;
+        }
+
+        let value = array[self.next];
+        let mut i = self.next;
+        while i > self.low {
+            *comparisons += 1;
+            if array[i - 1] <= value {
+                break;
+            }
+            array[i] = array[i - 1]; // Shift the larger element to the right.
+            *writes += 1;
+            i -= 1;
+        }
+
+        if i != self.next {
+            array[i] = value;
+            *swaps += 1;
+            *writes += 1;
+            play_beep();
+        }
+
+        self.focus = (i, self.next);
+        self.next += 1;
+        self.next >= self.high
+    }
+
+    /// Returns the indices touched by the last step.
+    pub(crate) fn focus(&self) -> (usize, usize) {
+        self.focus
+    }
+}
+
+/// Stepwise heapsort over the subrange `low..high` of an array.
+/// Each step performs one level of a sift-down or one root extraction.
+pub(crate) struct RangeHeap {
+    low: usize,            // First index of the subrange.
+    end: usize,            // Current heap size, relative to `low`.
+    next_root: usize,      // Next root to sift while building the heap.
+    sift: Option<usize>,   // Node currently being sifted down, relative to `low`.
+    building: bool,        // Whether the heap is still being built.
+    focus: (usize, usize), // Indices touched by the last step.
+}
+
+impl RangeHeap {
+    /// Creates a heapsort over `low..high`.
+    pub(crate) fn new(low: usize, high: usize) -> Self {
+        let len = high - low;
+        RangeHeap {
+            low,
+            end: len,
+            next_root: len / 2,
+            sift: None,
+            building: true,
+            focus: (low, low),
+        }
+    }
+
+    /// Performs one level of sift-down or extracts the current maximum.
+    /// # Returns
+    /// `true` once the whole subrange is sorted.
+    pub(crate) fn step(
+        &mut self,
+        array: &mut [usize],
+        comparisons: &mut usize,
+        swaps: &mut usize,
+        writes: &mut usize,
+    ) -> bool {
+        if let Some(node) = self.sift {
+            let mut child = node * 2 + 1;
+            if child >= self.end {
+                self.sift = None;
+                return false;
+            }
+            if child + 1 < self.end {
+                *comparisons += 1;
+                if array[self.low + child] < array[self.low + child + 1] {
+                    child += 1; // The right child is larger.
+                }
+            }
+
+            *comparisons += 1;
+            self.focus = (self.low + node, self.low + child);
+            if array[self.low + node] < array[self.low + child] {
+                array.swap(self.low + node, self.low + child);
+                *swaps += 1;
+                *writes += 2;
+                play_beep();
+                self.sift = Some(child);
+            } else {
+                self.sift = None; // The heap property holds below this node.
+            }
+            return false;
+        }
+
+        if self.building {
+            if self.next_root > 0 {
+                self.next_root -= 1;
+                self.sift = Some(self.next_root);
+                return false;
+            }
+            self.building = false;
+        }
+
+        if self.end <= 1 {
+            return true;
+        }
+
+        // Move the maximum behind the heap and restore the heap property.
+        self.end -= 1;
+        array.swap(self.low, self.low + self.end);
+        *swaps += 1;
+        *writes += 2;
+        play_beep();
+        self.focus = (self.low, self.low + self.end);
+        self.sift = Some(0);
+        false
+    }
+
+    /// Returns whether the heap has been built and no sift-down is in progress,
+    /// before the first root extraction.
+    pub(crate) fn is_heap(&self) -> bool {
+        self.building && self.next_root == 0 && self.sift.is_none()
+    }
+
+    /// Sifts the root down again after its value was replaced from outside the heap.
+    pub(crate) fn sift_root(&mut self) {
+        self.sift = Some(0);
+    }
+
+    /// Returns the first index of the sorted tail behind the heap.
+    pub(crate) fn sorted_from(&self) -> usize {
+        self.low + self.end
+    }
+
+    /// Returns the indices touched by the last step.
+    pub(crate) fn focus(&self) -> (usize, usize) {
+        self.focus
+    }
+}
+
+/// Reverses `array[low..high]` with swaps.
+pub(crate) fn reverse(
+    array: &mut [usize],
+    low: usize,
+    high: usize,
+    swaps: &mut usize,
+    writes: &mut usize,
+) {
+    let (mut i, mut j) = (low, high);
+    while i + 1 < j {
+        j -= 1;
+        array.swap(i, j);
+        *swaps += 1;
+        *writes += 2;
+        i += 1;
+    }
+}
+
+/// Rotates `array[low..high]` left by `amount` using three reversals, in place.
+pub(crate) fn rotate(
+    array: &mut [usize],
+    low: usize,
+    high: usize,
+    amount: usize,
+    swaps: &mut usize,
+    writes: &mut usize,
+) {
+    if amount == 0 || low + amount >= high {
+        return;
+    }
+    reverse(array, low, low + amount, swaps, writes);
+    reverse(array, low + amount, high, swaps, writes);
+    reverse(array, low, high, swaps, writes);
+}
+
+/// Returns the first index in `low..high` whose value is not less than `value`.
+pub(crate) fn lower_bound(
+    array: &[usize],
+    low: usize,
+    high: usize,
+    value: usize,
+    comparisons: &mut usize,
+) -> usize {
+    let (mut low, mut high) = (low, high);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        *comparisons += 1;
+        if array[middle] < value {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    low
+}
+
+/// Returns the first index in `low..high` whose value is greater than `value`.
+pub(crate) fn upper_bound(
+    array: &[usize],
+    low: usize,
+    high: usize,
+    value: usize,
+    comparisons: &mut usize,
+) -> usize {
+    let (mut low, mut high) = (low, high);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        *comparisons += 1;
+        if array[middle] <= value {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    low
+}
+
+/// Stepwise in-place merge of the sorted runs `low..mid` and `mid..high` without a buffer.
+/// Each step moves the elements of the right run that belong before the first element of
+/// the left run in front of it with one rotation, found by binary search.
+pub(crate) struct RangeRotationMerge {
+    low: usize,            // Start of the left run still to merge.
+    mid: usize,            // End of the left run and start of the right run.
+    high: usize,           // End of the right run.
+    focus: (usize, usize), // Bounds of the range rotated by the last step.
+}
+
+impl RangeRotationMerge {
+    /// Creates an in-place merge of `low..mid` and `mid..high`.
+    pub(crate) fn new(low: usize, mid: usize, high: usize) -> Self {
+        RangeRotationMerge {
+            low,
+            mid,
+            high,
+            focus: (usize::MAX, usize::MAX),
+        }
+    }
+
+    /// Performs one binary search and one rotation.
+    /// # Returns
+    /// `true` once both runs are merged.
+    pub(crate) fn step(
+        &mut self,
+        array: &mut [usize],
+        comparisons: &mut usize,
+        swaps: &mut usize,
+        writes: &mut usize,
+    ) -> bool {
+        if self.low >= self.mid || self.mid >= self.high {
+            return true;
+        }
+
+        // Right-run elements smaller than the first left element move in front of the left run.
+        let split = lower_bound(array, self.mid, self.high, array[self.low], comparisons);
+        let moved = split - self.mid;
+        rotate(array, self.low, split, self.mid - self.low, swaps, writes);
+        if moved > 0 {
+            play_beep();
+        }
+        self.focus = (self.low, split.saturating_sub(1));
+
+        // Left elements equal to the first one are now in place as well.
+        let low = self.low + moved;
+        self.mid = split;
+        self.low = upper_bound(array, low, self.mid, array[low], comparisons);
+        self.low >= self.mid || self.mid >= self.high
+    }
+
+    /// Returns the bounds of the range rotated by the last step.
+    pub(crate) fn focus(&self) -> (usize, usize) {
+        self.focus
+    }
+}
+
+/// Runs a whole `Sorter` stepwise over the subrange `low..high` of an array.
+/// The sorter works on a copy of the subrange, which is written back after every step.
+pub(crate) struct RangeSorter {
+    sorter: Box<dyn Sorter>, // Sorts the subrange, reset before each new one.
+    low: usize,              // First index of the subrange.
+    scratch: Vec<usize>,     // Copy of the subrange handed to the sorter.
+    focus: (usize, usize),   // Indices touched by the last step.
+}
+
+impl RangeSorter {
+    /// Wraps `sorter` so it can sort subranges.
+    pub(crate) fn new(sorter: Box<dyn Sorter>) -> Self {
+        RangeSorter {
+            sorter,
+            low: 0,
+            scratch: Vec::new(),
+            focus: (usize::MAX, usize::MAX),
+        }
+    }
+
+    /// Starts sorting `low..high`, abandoning any subrange in progress.
+    pub(crate) fn start(&mut self, array: &[usize], low: usize, high: usize) {
+        self.sorter.reset_state();
+        self.low = low;
+        self.scratch = array[low..high].to_vec();
+    }
+
+    /// Runs one step of the sorter on the subrange and writes the result back.
+    /// Counters are taken as deltas, so sorters that keep them across resets still add up.
+    /// # Returns
+    /// `true` once the subrange is sorted.
+    pub(crate) fn step(
+        &mut self,
+        array: &mut [usize],
+        comparisons: &mut usize,
+        swaps: &mut usize,
+        writes: &mut usize,
+    ) -> bool {
+        let before = (
+            self.sorter.comparisons(),
+            self.sorter.swaps(),
+            self.sorter.writes(),
+        );
+        let done = self.sorter.step(&mut self.scratch);
+        *comparisons += self.sorter.comparisons() - before.0;
+        *swaps += self.sorter.swaps() - before.1;
+        *writes += self.sorter.writes() - before.2;
+
+        let (low, len) = (self.low, self.scratch.len());
+        array[low..low + len].copy_from_slice(&self.scratch);
+        let (a, b) = self.sorter.special();
+        let offset = |i: usize| if i < len { low + i } else { usize::MAX };
+        self.focus = (offset(a), offset(b));
+
+        if done {
+            self.scratch.clear();
+        }
+        done
+    }
+
+    /// Returns whether a subrange has been started and is not sorted yet.
+    pub(crate) fn is_running(&self) -> bool {
+        !self.scratch.is_empty()
+    }
+
+    /// Returns the indices touched by the last step.
+    pub(crate) fn focus(&self) -> (usize, usize) {
+        self.focus
+    }
+
+    /// Returns the reason reported by the sorter for its last step.
+    pub(crate) fn reason(&self) -> Reasons {
+        self.sorter.reason()
+    }
+
+    /// Returns the number of elements the sorter buffers outside the array.
+    /// The copy of the subrange is not counted: it only exists because a `Sorter` steps
+    /// a whole `Vec`, and stands in for the subrange itself.
+    pub(crate) fn auxiliary_memory(&self) -> usize {
+        self.sorter.auxiliary_memory()
+    }
+
+    /// Resets the wrapped sorter and drops any subrange in progress.
+    pub(crate) fn reset(&mut self) {
+        self.sorter.reset_state();
+        self.scratch.clear();
+        self.focus = (usize::MAX, usize::MAX);
+    }
+}
@@ -0,0 +1,195 @@
+use super::structure::Structure;
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+use std::borrow::Cow;
+
+/// The progress of a single recursive call.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum FrameState {
+    /// Created by its parent but not yet reached.
+    Waiting,
+    /// Split into two halves that are being sorted.
+    Split,
+    /// Its subarray is sorted.
+    Done,
+}
+
+/// A recursive call of the top-down merge sort, covering `low..high`.
+#[derive(Clone)]
+pub struct Frame {
+    pub low: usize,        // First index of the subarray.
+    pub high: usize,       // One past the last index of the subarray.
+    pub depth: usize,      // Recursion depth, 0 for the whole array.
+    pub state: FrameState, // How far the call has progressed.
+}
+
+/// Represents the top-down recursive MergeSort and its state.
+/// The recursion is unrolled into an explicit stack: a frame is split into two child
+/// frames the first time it is reached, and its halves are merged once both are done.
+pub struct TopDownMergeSort {
+    frames: Vec<Frame>,      // Every frame created so far, in creation order.
+    stack: Vec<usize>,       // Frames still to be processed, the top last.
+    current: Option<usize>,  // Frame handled by the last step.
+    temp: Vec<usize>,        // Temporary array used during merging.
+    special: (usize, usize), // Indices touched by the last step.
+    reason: Reasons,         // Reason for the current operation.
+    finished: bool,          // Indicates whether the sorting is complete.
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+}
+
+impl TopDownMergeSort {
+    /// Adds a frame for `low..high` and pushes it onto the stack.
+    fn push(&mut self, low: usize, high: usize, depth: usize) {
+        self.frames.push(Frame {
+            low,
+            high,
+            depth,
+            state: FrameState::Waiting,
+        });
+        self.stack.push(self.frames.len() - 1);
+    }
+
+    /// Merges the sorted halves `low..mid` and `mid..high` through the temporary array.
+    fn merge(&mut self, array: &mut [usize], low: usize, mid: usize, high: usize) {
+        let (mut left, mut right) = (low, mid);
+        for slot in low..high {
+            let take_left = right >= high
+                || left < mid && {
+                    self.comparisons += 1;
+                    array[left] <= array[right]
+                };
+            if take_left {
+                self.temp[slot] = array[left];
+                left += 1;
+            } else {
+                self.temp[slot] = array[right];
+                right += 1;
+            }
+        }
+        array[low..high].copy_from_slice(&self.temp[low..high]);
+        self.writes += high - low;
+    }
+}
+
+impl Sorter for TopDownMergeSort {
+    /// Creates a new instance of TopDownMergeSort.
+    fn new() -> Self {
+        TopDownMergeSort {
+            frames: Vec::new(),
+            stack: Vec::new(),
+            current: None,
+            temp: Vec::new(),
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+
+    /// Returns the bounds of the subarray handled by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step: splits the frame on top of the stack,
+    /// or merges its halves once both are sorted.
+    /// Frames of a single element are already sorted and are finished without a step.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+
+        // The first step creates the frame for the whole array.
+        if self.frames.is_empty() {
+            self.temp = vec![0; array.len()];
+            self.push(0, array.len(), 0);
+        }
+
+        while let Some(&id) = self.stack.last() {
+            let Frame {
+                low,
+                high,
+                depth,
+                state,
+            } = self.frames[id];
+            self.current = Some(id);
+            self.special = (low, high.saturating_sub(1));
+
+            if high - low <= 1 {
+                self.frames[id].state = FrameState::Done;
+                self.stack.pop();
+                continue;
+            }
+
+            let mid = low + (high - low) / 2;
+            if state == FrameState::Waiting {
+                // Recurse: the right half is pushed first so the left half runs first.
+                self.frames[id].state = FrameState::Split;
+                self.push(mid, high, depth + 1);
+                self.push(low, mid, depth + 1);
+                self.reason = Reasons::Comparing;
+            } else {
+                self.merge(array, low, mid, high);
+                self.frames[id].state = FrameState::Done;
+                self.stack.pop();
+                self.reason = Reasons::Switching;
+                self.swaps += 1;
+                play_beep();
+            }
+            return false;
+        }
+
+        self.finished = true;
+        self.current = None;
+        self.special = (usize::MAX, usize::MAX);
+        true
+    }
+
+    /// Resets the state of the TopDownMergeSort instance for a fresh sort.
+    fn reset_state(&mut self) {
+        *self = Self::new(); // Reset all fields to their initial state.
+    }
+
+    /// Checks if the sorting process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    fn auxiliary_memory(&self) -> usize {
+        self.temp.len()
+    }
+
+    /// Returns the recursion tree.
+    fn structure(&self) -> Option<Structure<'_>> {
+        if self.frames.is_empty() {
+            return None;
+        }
+        Some(Structure::RecursionTree {
+            len: self.temp.len(),
+            frames: Cow::Borrowed(&self.frames),
+            current: self.current,
+            stack_depth: self.stack.len(),
+        })
+    }
+}
@@ -0,0 +1,207 @@
+use super::structure::Structure;
+use super::{Reasons, Sorter};
+use crate::sound::play_beep;
+use std::borrow::Cow;
+
+/// A node of the binary search tree built by TreeSort.
+#[derive(Clone)]
+pub struct Node {
+    pub value: usize,         // The value stored in the node.
+    pub left: Option<usize>,  // Index of the left child in the node list.
+    pub right: Option<usize>, // Index of the right child in the node list.
+    pub written: bool,        // Whether the value has been written back to the array.
+}
+
+/// Represents the TreeSort algorithm and its state.
+/// Every element is inserted into an unbalanced binary search tree, one comparison per step,
+/// and an in-order traversal then writes the values back, one per step.
+/// Equal values go to the right subtree, which keeps the sort stable.
+pub struct TreeSort {
+    nodes: Vec<Node>,        // The tree, with the root at index 0.
+    next: usize,             // Next array element to insert.
+    descend: Option<usize>,  // Node the element being inserted is compared with.
+    stack: Vec<usize>,       // Explicit stack of the in-order traversal.
+    cursor: Option<usize>,   // Next subtree to descend into during the traversal.
+    output: usize,           // Next array index to write during the traversal.
+    building: bool,          // Whether the tree is still being built.
+    focus: Option<usize>,    // Node touched by the last step.
+    special: (usize, usize), // Indices touched by the last step.
+    reason: Reasons,         // Reason for the current operation.
+    finished: bool,          // Indicates whether the sorting is complete.
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+}
+
+impl TreeSort {
+    /// Compares the element being inserted with the current node and moves one level down,
+    /// attaching a new leaf once an empty child is reached.
+    fn insert_step(&mut self, array: &[usize]) {
+        let value = array[self.next];
+        let Some(node) = self.descend else {
+            // The first element becomes the root without any comparison.
+            self.attach(value);
+            return;
+        };
+
+        self.comparisons += 1;
+        self.focus = Some(node);
+        self.special = (self.next, self.next);
+        self.reason = Reasons::Comparing;
+
+        let leaf = self.nodes.len();
+        let child = if value < self.nodes[node].value {
+            &mut self.nodes[node].left
+        } else {
+            &mut self.nodes[node].right
+        };
+        match *child {
+            Some(child) => self.descend = Some(child),
+            None => {
+                *child = Some(leaf);
+                self.attach(value);
+            }
+        }
+    }
+
+    /// Adds a new node for `value` and starts the insertion of the next element.
+    fn attach(&mut self, value: usize) {
+        self.nodes.push(Node {
+            value,
+            left: None,
+            right: None,
+            written: false,
+        });
+        self.focus = Some(self.nodes.len() - 1);
+        self.next += 1;
+        self.descend = Some(0);
+    }
+
+    /// Writes the next value of the in-order traversal back to the array.
+    /// # Returns
+    /// `true` once every value has been written.
+    fn write_step(&mut self, array: &mut [usize]) -> bool {
+        while let Some(node) = self.cursor {
+            self.stack.push(node);
+            self.cursor = self.nodes[node].left;
+        }
+        let Some(node) = self.stack.pop() else {
+            return true;
+        };
+
+        array[self.output] = self.nodes[node].value;
+        self.nodes[node].written = true;
+        self.writes += 1;
+        self.focus = Some(node);
+        self.special = (self.output, self.output);
+        self.reason = Reasons::Switching;
+        play_beep();
+
+        self.output += 1;
+        self.cursor = self.nodes[node].right;
+        false
+    }
+}
+
+impl Sorter for TreeSort {
+    /// Creates a new instance of TreeSort.
+    fn new() -> Self {
+        TreeSort {
+            nodes: Vec::new(),
+            next: 0,
+            descend: None,
+            stack: Vec::new(),
+            cursor: None,
+            output: 0,
+            building: true,
+            focus: None,
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+
+    /// Returns the indices touched by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Returns the reason for the current sorting action.
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Executes a single step: one comparison while building the tree,
+    /// or one write during the in-order traversal.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+
+        if self.building && self.next < array.len() {
+            self.insert_step(array);
+            return false;
+        }
+        if self.building {
+            self.building = false;
+            self.cursor = if self.nodes.is_empty() { None } else { Some(0) };
+        }
+
+        if self.write_step(array) {
+            self.finished = true;
+            self.focus = None;
+            self.special = (usize::MAX, usize::MAX);
+            return true;
+        }
+        false
+    }
+
+    /// Resets the state of the TreeSort instance for a fresh sort.
+    fn reset_state(&mut self) {
+        *self = Self::new(); // Reset all fields to their initial state.
+    }
+
+    /// Checks if the sorting process is finished.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    fn auxiliary_memory(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns whether the tree is being built or traversed.
+    fn phase(&self) -> Option<&'static str> {
+        match (self.building, self.finished) {
+            (_, true) => None,
+            (true, false) => Some("Building the search tree"),
+            (false, false) => Some("In-order write-back"),
+        }
+    }
+
+    /// Returns the binary search tree.
+    fn structure(&self) -> Option<Structure<'_>> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        Some(Structure::SearchTree {
+            nodes: Cow::Borrowed(&self.nodes),
+            focus: self.focus,
+        })
+    }
+}
@@ -0,0 +1,264 @@
+//! An opt-in HTTP server on localhost for driving the visualizer from scripts,
+//! started with `--api PORT`. Requests are handed to the UI thread, which applies them
+//! like the buttons do and answers with its state as JSON:
+//!
+//! * `GET /state` - The state, see `Status`.
+//! * `GET /algorithms` - The names `POST /algorithm` accepts.
+//! * `POST /numbers` - Sets the numbers from a JSON array or a comma-separated body.
+//! * `POST /algorithm` - Selects the algorithm named in the body.
+//! * `POST /start`, `/stop`, `/step`, `/reset`, `/shuffle` - Press the button of that name.
+//! * `GET /events` - A stream of one JSON line per step, in the event format of trace files.
+//!   While no steps are taken, an empty line is sent every few seconds so closed streams
+//!   are noticed. Readers should skip blank lines.
+//!
+//! For example `curl -X POST -d '[5,3,1]' localhost:7878/numbers`, or
+//! `curl -N localhost:7878/events` to follow a run. Errors come back as `{"error": "..."}`.
+
+use crate::trace::Event;
+use crate::types::Algorithms;
+use serde::Serialize;
+use serde_json::json;
+use std::io::{self, Cursor, Write};
+use std::net::SocketAddr;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryIter};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use strum::IntoEnumIterator;
+use tiny_http::{Header, Method, Response, Server};
+
+/// How long a request waits for the UI thread before giving up.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long an `/events` stream stays silent before an empty line checks the connection.
+const HEARTBEAT: Duration = Duration::from_secs(5);
+
+/// An action requested through the API.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Status,
+    Numbers(Vec<usize>),
+    Algorithm(Algorithms),
+    Start,
+    Stop,
+    Step,
+    Reset,
+    Shuffle,
+}
+
+/// The state of the visualizer returned by every request.
+#[derive(Serialize)]
+pub struct Status {
+    pub algorithm: String, // Name of the selected algorithm, as `POST /algorithm` accepts it.
+    pub state: String,     // "Start", "Running" or "Finished".
+    pub numbers: Vec<usize>,
+    pub comparisons: usize,
+    pub swaps: usize,
+    pub writes: usize,
+    pub elapsed: f64, // Seconds since the sort was started.
+}
+
+/// A command waiting for the UI thread, which must answer it with `reply`.
+pub struct Request {
+    pub command: Command,
+    reply: Sender<Result<Status, String>>,
+}
+
+impl Request {
+    /// Answers the request with the state after the command, or why it was refused.
+    pub fn reply(self, result: Result<Status, String>) {
+        // The client may have given up waiting, which is not the UI's problem.
+        let _ = self.reply.send(result);
+    }
+}
+
+/// The running server, polled by the UI thread for requests.
+pub struct Api {
+    server: Arc<Server>,
+    requests: Receiver<Request>,
+    subscribers: Arc<Mutex<Vec<Sender<String>>>>, // Clients of `/events`, fed one line per step.
+}
+
+impl Api {
+    /// Starts serving on `127.0.0.1:port` on a background thread.
+    /// Port 0 picks a free port, see `address`.
+    pub fn start(port: u16) -> io::Result<Self> {
+        let server = Server::http(("127.0.0.1", port)).map_err(io::Error::other)?;
+        let server = Arc::new(server);
+        let (sender, requests) = mpsc::channel();
+        let subscribers = Arc::new(Mutex::new(Vec::new()));
+        {
+            let (server, subscribers) = (server.clone(), subscribers.clone());
+            thread::spawn(move || serve(&server, &sender, &subscribers));
+        }
+        Ok(Api {
+            server,
+            requests,
+            subscribers,
+        })
+    }
+
+    /// Returns the address the server listens on.
+    pub fn address(&self) -> SocketAddr {
+        self.server
+            .server_addr()
+            .to_ip()
+            .expect("the server listens on TCP")
+    }
+
+    /// Returns the requests received since the last call, without waiting.
+    pub fn requests(&self) -> TryIter<'_, Request> {
+        self.requests.try_iter()
+    }
+
+    /// Sends `event` to every client of `/events`, dropping those that disconnected.
+    pub fn publish(&self, event: &Event) {
+        let line = serde_json::to_string(event).expect("an event always serializes");
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| subscriber.send(line.clone()).is_ok());
+    }
+}
+
+impl Drop for Api {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+/// Answers requests until the server is unblocked, passing commands to the UI thread.
+fn serve(server: &Server, commands: &Sender<Request>, subscribers: &Mutex<Vec<Sender<String>>>) {
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        if request.as_reader().read_to_string(&mut body).is_err() {
+            let _ = request.respond(error_response(400, "the body is not UTF-8"));
+            continue;
+        }
+        let path = request
+            .url()
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let response = match (request.method(), path.as_str()) {
+            (Method::Get, "/events") => {
+                let (sender, receiver) = mpsc::channel();
+                subscribers.lock().unwrap().push(sender);
+                thread::spawn(move || stream_events(request.into_writer(), &receiver));
+                continue;
+            }
+            (Method::Get, "/algorithms") => {
+                let names: Vec<String> = Algorithms::iter().map(|a| format!("{:?}", a)).collect();
+                json_response(200, &json!(names))
+            }
+            (method, path) => match parse_command(method, path, &body) {
+                Ok(command) => execute(commands, command),
+                Err((code, message)) => error_response(code, &message),
+            },
+        };
+        let _ = request.respond(response);
+    }
+}
+
+/// Hands `command` to the UI thread and turns its answer into a response.
+fn execute(commands: &Sender<Request>, command: Command) -> Response<Cursor<Vec<u8>>> {
+    let (reply, answer) = mpsc::channel();
+    if commands.send(Request { command, reply }).is_err() {
+        return error_response(503, "the visualizer has closed");
+    }
+    match answer.recv_timeout(REPLY_TIMEOUT) {
+        Ok(Ok(status)) => json_response(200, &json!(status)),
+        Ok(Err(message)) => error_response(409, &message),
+        Err(_) => error_response(503, "the visualizer did not answer in time"),
+    }
+}
+
+/// Maps a request to a command.
+/// # Returns
+/// The command, or the status code and message of the error to answer with.
+fn parse_command(method: &Method, path: &str, body: &str) -> Result<Command, (u16, String)> {
+    let command = match path {
+        "/state" => Command::Status,
+        "/numbers" => Command::Numbers(parse_numbers(body).ok_or((
+            400,
+            "expected a non-empty list of numbers, like [5,3,1] or 5,3,1".to_string(),
+        ))?),
+        "/algorithm" => {
+            let name = body.trim().trim_matches('"');
+            let algorithm = Algorithms::iter().find(|algorithm| {
+                format!("{:?}", algorithm).eq_ignore_ascii_case(name)
+                    || algorithm.label().eq_ignore_ascii_case(name)
+            });
+            Command::Algorithm(algorithm.ok_or((
+                400,
+                format!("unknown algorithm {:?}, see /algorithms", name),
+            ))?)
+        }
+        "/start" => Command::Start,
+        "/stop" => Command::Stop,
+        "/step" => Command::Step,
+        "/reset" => Command::Reset,
+        "/shuffle" => Command::Shuffle,
+        _ => return Err((404, format!("no such endpoint {}", path))),
+    };
+    let expected = if command == Command::Status {
+        Method::Get
+    } else {
+        Method::Post
+    };
+    if *method != expected {
+        return Err((405, format!("{} expects {}", path, expected)));
+    }
+    Ok(command)
+}
+
+/// Reads numbers given as a JSON array or separated by commas.
+fn parse_numbers(body: &str) -> Option<Vec<usize>> {
+    let numbers = serde_json::from_str::<Vec<usize>>(body).ok().or_else(|| {
+        body.split(',')
+            .map(|n| n.trim().parse::<usize>().ok())
+            .collect()
+    })?;
+    (!numbers.is_empty()).then_some(numbers)
+}
+
+/// Writes every line from `events` to a client of `/events` until it disconnects.
+/// An empty line is written after every `HEARTBEAT` without events, so a client that went
+/// away while the visualizer is idle fails the write and frees its thread.
+fn stream_events(mut writer: Box<dyn Write + Send>, events: &Receiver<String>) {
+    // The response is written by hand, as tiny_http buffers chunked bodies.
+    let header = "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\n\
+                  Cache-Control: no-cache\r\nConnection: close\r\n\r\n";
+    if writer
+        .write_all(header.as_bytes())
+        .and_then(|_| writer.flush())
+        .is_err()
+    {
+        return;
+    }
+    loop {
+        let line = match events.recv_timeout(HEARTBEAT) {
+            Ok(line) => line,
+            Err(RecvTimeoutError::Timeout) => String::new(),
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        let sent = writeln!(writer, "{}", line).and_then(|_| writer.flush());
+        if sent.is_err() {
+            return;
+        }
+    }
+}
+
+/// Returns a response with `body` as JSON.
+fn json_response(code: u16, body: &serde_json::Value) -> Response<Cursor<Vec<u8>>> {
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+    Response::from_string(body.to_string())
+        .with_status_code(code)
+        .with_header(content_type)
+}
+
+/// Returns an error response with `message`.
+fn error_response(code: u16, message: &str) -> Response<Cursor<Vec<u8>>> {
+    json_response(code, &json!({ "error": message }))
+}
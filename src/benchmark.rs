@@ -0,0 +1,146 @@
+use crate::algorithms::shell_sort::{GapSequence, ShellSort};
+use crate::algorithms::{input_limit, Sorter};
+use crate::sound;
+use crate::types::Algorithms;
+use strum::IntoEnumIterator;
+
+/// Maximum number of steps an algorithm may take in a benchmark before it is abandoned.
+pub const MAX_BENCHMARK_STEPS: usize = 200_000;
+
+/// Counters collected by running one algorithm to completion without the UI.
+pub struct BenchmarkResult {
+    pub name: String,
+    pub parameters: String, // The algorithm's parameter values, empty if it has none.
+    pub steps: usize,
+    pub comparisons: usize,
+    pub swaps: usize,
+    pub writes: usize,
+    pub peak_memory: usize, // Most elements held outside the array at any step.
+    pub finished: bool,     // `false` if the step limit was reached first.
+}
+
+/// Runs `sorter` on `array` until it finishes or `max_steps` steps have been taken.
+/// # Returns
+/// The number of steps taken, whether the sorter finished, and its peak auxiliary memory.
+pub fn run_to_completion(
+    sorter: &mut dyn Sorter,
+    array: &mut Vec<usize>,
+    max_steps: usize,
+) -> (usize, bool, usize) {
+    sound::muted(|| {
+        let (mut steps, mut peak_memory) = (0, 0);
+        while steps < max_steps {
+            steps += 1;
+            let finished = sorter.step(array);
+            peak_memory = peak_memory.max(sorter.auxiliary_memory());
+            if finished {
+                return (steps, true, peak_memory);
+            }
+        }
+        (steps, false, peak_memory)
+    })
+}
+
+/// Runs every algorithm on a copy of `numbers` and collects its counters.
+/// # Arguments
+/// * `numbers` - The input shared by all algorithms.
+/// * `create` - Builds the sorter for an algorithm, with the user's current parameters.
+/// * `describe` - Describes the parameters `create` used for an algorithm.
+/// * `done` - Called after every algorithm, to report progress.
+pub fn run_all(
+    numbers: &[usize],
+    create: impl Fn(Algorithms) -> Box<dyn Sorter>,
+    describe: impl Fn(Algorithms) -> String,
+    mut done: impl FnMut(),
+) -> Vec<BenchmarkResult> {
+    Algorithms::iter()
+        .map(|algorithm| {
+            let name = algorithm.label();
+            let result = run(name, describe(algorithm), create(algorithm), numbers);
+            done();
+            result
+        })
+        .collect()
+}
+
+/// Runs ShellSort with every gap sequence on a copy of `numbers`,
+/// calling `done` after every sequence.
+pub fn run_gap_sequences(numbers: &[usize], mut done: impl FnMut()) -> Vec<BenchmarkResult> {
+    GapSequence::iter()
+        .map(|sequence| {
+            let name = format!("Shell Sort ({:?} gaps)", sequence);
+            let parameters = format!("Gaps={:?}", sequence);
+            let result = run(
+                name,
+                parameters,
+                Box::new(ShellSort::with_sequence(sequence)),
+                numbers,
+            );
+            done();
+            result
+        })
+        .collect()
+}
+
+/// Returns the number of runs `run_all` and `run_gap_sequences` make together.
+pub fn run_count() -> usize {
+    Algorithms::iter().count() + GapSequence::iter().count()
+}
+
+/// Runs a single sorter on a copy of `numbers` and collects its counters under `name`.
+/// Algorithms too slow for the whole input only get the part `input_limit` allows.
+fn run(
+    name: String,
+    mut parameters: String,
+    mut sorter: Box<dyn Sorter>,
+    numbers: &[usize],
+) -> BenchmarkResult {
+    let limit = input_limit(sorter.as_ref(), numbers.len());
+    if limit < numbers.len() {
+        if !parameters.is_empty() {
+            parameters.push_str("; ");
+        }
+        parameters.push_str(&format!("Input=first {} elements", limit));
+    }
+    let mut array = numbers[..limit].to_vec();
+    let (steps, finished, peak_memory) =
+        run_to_completion(sorter.as_mut(), &mut array, MAX_BENCHMARK_STEPS);
+    BenchmarkResult {
+        name,
+        parameters,
+        steps,
+        comparisons: sorter.comparisons(),
+        swaps: sorter.swaps(),
+        writes: sorter.writes(),
+        peak_memory,
+        finished,
+    }
+}
+
+/// Formats benchmark results as CSV, one row per run with a header row first.
+/// Fields containing commas or quotes are quoted.
+pub fn to_csv(results: &[BenchmarkResult]) -> String {
+    let quote = |field: &str| {
+        if field.contains([',', '"']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    };
+    let mut csv =
+        String::from("algorithm,parameters,steps,finished,comparisons,swaps,writes,peak_memory\n");
+    for result in results {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            quote(&result.name),
+            quote(&result.parameters),
+            result.steps,
+            result.finished,
+            result.comparisons,
+            result.swaps,
+            result.writes,
+            result.peak_memory
+        ));
+    }
+    csv
+}
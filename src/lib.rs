@@ -0,0 +1,10 @@
+//! The sorting algorithms of the visualizer and everything they need to run without a window,
+//! so the tests and fuzz targets can drive them directly.
+
+pub mod algorithms;
+pub mod params;
+pub mod rng;
+pub mod shuffle;
+pub mod sound;
+pub mod trace;
+pub mod types;
@@ -1,13 +1,52 @@
 use eframe::{epaint::Vec2, run_native, NativeOptions};
+use std::path::Path;
+use std::process;
 
-mod algorithms;
+use sorting_visualizer::{algorithms, params, shuffle, sound, trace, types};
+
+mod api;
+mod benchmark;
 mod random;
-mod sound;
-mod types;
 mod ui;
 
 /// The entry point for the sorting algorithm visualizer application.
+/// With `--trace FILE` it replays a trace file, and with `--trace -` the operations of an
+/// external program read from standard input.
+/// With `--api PORT` it can be driven from scripts through a server on localhost, see `api`.
 fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut trace = None;
+    let mut api = None;
+    while let Some(flag) = args.next() {
+        match (flag.as_str(), args.next()) {
+            ("--trace", Some(path)) => match trace::Trace::load(Path::new(&path)) {
+                Ok(loaded) => trace = Some(loaded),
+                Err(error) => {
+                    eprintln!("Cannot replay {}: {}", path, error);
+                    process::exit(1);
+                }
+            },
+            ("--api", Some(port)) => match port.parse().map(api::Api::start) {
+                Ok(Ok(server)) => {
+                    println!("Control API listening on http://{}", server.address());
+                    api = Some(server);
+                }
+                Ok(Err(error)) => {
+                    eprintln!("Cannot start the control API on port {}: {}", port, error);
+                    process::exit(1);
+                }
+                Err(_) => {
+                    eprintln!("Invalid port {}", port);
+                    process::exit(2);
+                }
+            },
+            _ => {
+                eprintln!("Usage: sorting-visualizer [--trace FILE | --trace -] [--api PORT]");
+                process::exit(2);
+            }
+        }
+    }
+
     // Define the native options for the application window.
     let native_options = NativeOptions {
         initial_window_size: Some(Vec2::new(1650., 730.)), // Set the initial window size.
@@ -18,7 +57,7 @@ fn main() {
     run_native(
         "Sorting Visualizer", // The title of the application window.
         native_options,       // The native options defined above.
-        Box::new(|cc| Box::new(ui::Visualizer::new(cc))), // Create a new Visualizer instance.
+        Box::new(move |cc| Box::new(ui::Visualizer::new(cc, trace, api))), // Create a new Visualizer instance.
     )
     .unwrap(); // Unwrap any errors that occur during the application run.
 }
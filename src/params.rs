@@ -0,0 +1,422 @@
+use crate::algorithms::{
+    bitonic_sort::BitonicSort,
+    block_merge_sort::{BlockMergeSort, DEFAULT_INSERTION_RUN},
+    bogo_sort::BogoSort,
+    bubble_sort::BubbleSort,
+    bucket_sort::{BucketSort, Splitters},
+    cocktail_sort::CocktailSort,
+    comb_sort::{CombSort, DEFAULT_SHRINK},
+    counting_sort::CountingSort,
+    cycle_sort::CycleSort,
+    gnome_sort::GnomeSort,
+    heap_sort::HeapSort,
+    heap_top_k::{HeapTopK, DEFAULT_K_PERCENT},
+    hybrid_sort::{HybridSort, Outer, DEFAULT_CUTOFF},
+    insertion_sort::InsertionSort,
+    intro_sort::{IntroSort, DEFAULT_INSERTION_THRESHOLD},
+    merge_sort::MergeSort,
+    odd_even_merge_sort::OddEvenMergeSort,
+    odd_even_sort::OddEvenSort,
+    pancake_sort::PancakeSort,
+    partial_insertion_sort::PartialInsertionSort,
+    patience_sort::PatienceSort,
+    pdq_sort::PdqSort,
+    permutation_sort::PermutationSort,
+    quick_select::{QuickSelect, DEFAULT_TARGET_PERCENT},
+    quick_sort::{PartitionScheme, PivotStrategy, QuickSort},
+    rotation_merge_sort::RotationMergeSort,
+    selection_sort::SelectionSort,
+    shell_sort::{GapSequence, ShellSort},
+    slow_sort::SlowSort,
+    smooth_sort::SmoothSort,
+    stooge_sort::StoogeSort,
+    top_down_merge_sort::TopDownMergeSort,
+    tree_sort::TreeSort,
+    Sorter,
+};
+use crate::types::Algorithms;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use strum::IntoEnumIterator;
+
+/// The kind of value a parameter takes, with its allowed range.
+pub enum ParamKind {
+    /// A whole number in `min..=max`, edited with a slider.
+    Integer { min: usize, max: usize },
+    /// A decimal number in `min..=max`, edited with a slider.
+    Decimal { min: f64, max: f64 },
+    /// One of the listed options, edited with a dropdown.
+    Choice {
+        options: Vec<String>, // Labels shown in the dropdown.
+        names: Vec<String>,   // Names saved in files, in the same order.
+    },
+}
+
+/// The value of a parameter. A choice is stored as the index of the selected option.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ParamValue {
+    Integer(usize),
+    Decimal(f64),
+    Choice(usize),
+}
+
+/// A parameter value as saved in files. A choice is saved by the name of the selected
+/// option, so files keep their meaning when options are added or reordered.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub enum SavedValue {
+    Integer(usize),
+    Decimal(f64),
+    Choice(String),
+}
+
+/// A tunable constant of an algorithm, passed to its constructor.
+pub struct Parameter {
+    pub name: &'static str,  // Label in the settings panel and exported stats.
+    pub kind: ParamKind,     // The kind of value and its range.
+    pub default: ParamValue, // The value used until the user changes it.
+}
+
+impl ParamValue {
+    /// Returns the value as a whole number, or 0 if it is not one.
+    pub fn integer(self) -> usize {
+        match self {
+            ParamValue::Integer(value) | ParamValue::Choice(value) => value,
+            ParamValue::Decimal(value) => value as usize,
+        }
+    }
+
+    /// Returns the value as a decimal number.
+    pub fn decimal(self) -> f64 {
+        match self {
+            ParamValue::Decimal(value) => value,
+            ParamValue::Integer(value) | ParamValue::Choice(value) => value as f64,
+        }
+    }
+
+    /// Returns the selected variant of the enum `T`, falling back to its first variant.
+    pub fn choice<T: IntoEnumIterator>(self) -> T {
+        T::iter()
+            .nth(self.integer())
+            .or_else(|| T::iter().next())
+            .expect("choice enums have at least one variant")
+    }
+}
+
+impl Parameter {
+    /// Converts `value` to the form saved in files.
+    pub fn save(&self, value: ParamValue) -> SavedValue {
+        match (&self.kind, value) {
+            (ParamKind::Choice { names, .. }, ParamValue::Choice(index)) => {
+                SavedValue::Choice(names.get(index).cloned().unwrap_or_default())
+            }
+            (_, ParamValue::Decimal(value)) => SavedValue::Decimal(value),
+            (_, value) => SavedValue::Integer(value.integer()),
+        }
+    }
+
+    /// Reads a value saved by `save`, checking it against the parameter's kind and range.
+    /// # Returns
+    /// The value, or a description of why it does not fit.
+    pub fn load(&self, saved: &SavedValue) -> Result<ParamValue, String> {
+        match (&self.kind, saved) {
+            (ParamKind::Integer { min, max }, &SavedValue::Integer(value)) => {
+                if (*min..=*max).contains(&value) {
+                    Ok(ParamValue::Integer(value))
+                } else {
+                    Err(format!(
+                        "parameter {}: {} is outside {}..={}",
+                        self.name, value, min, max
+                    ))
+                }
+            }
+            (ParamKind::Decimal { min, max }, &SavedValue::Decimal(value)) => {
+                if (*min..=*max).contains(&value) {
+                    Ok(ParamValue::Decimal(value))
+                } else {
+                    Err(format!(
+                        "parameter {}: {} is outside {}..={}",
+                        self.name, value, min, max
+                    ))
+                }
+            }
+            (ParamKind::Choice { names, .. }, SavedValue::Choice(name)) => names
+                .iter()
+                .position(|option| option == name)
+                .map(ParamValue::Choice)
+                .ok_or_else(|| format!("parameter {}: unknown option {:?}", self.name, name)),
+            _ => Err(format!("parameter {}: wrong kind of value", self.name)),
+        }
+    }
+}
+
+/// Lists the names of an enum's variants as dropdown options.
+fn options<T: IntoEnumIterator + Debug>() -> ParamKind {
+    let names: Vec<String> = T::iter().map(|variant| format!("{:?}", variant)).collect();
+    ParamKind::Choice {
+        options: names.clone(),
+        names,
+    }
+}
+
+/// Returns the algorithms that can sort the buckets of Bucket and Sample Sort
+/// and the short ranges of a Hybrid Sort.
+/// Algorithms built around a sub-sorter are excluded so construction cannot recurse,
+/// and so are partial algorithms, which would leave the ranges unsorted. Slow algorithms
+/// are excluded too: the input cap only sees the outer sorter, so they would never finish.
+pub fn sub_sorts() -> Vec<Algorithms> {
+    Algorithms::iter()
+        .filter(|algorithm| {
+            !algorithm.is_partial()
+                && !algorithm.is_slow()
+                && !matches!(
+                    algorithm,
+                    Algorithms::Bucket | Algorithms::Sample | Algorithms::Hybrid
+                )
+        })
+        .collect()
+}
+
+/// Declares a dropdown of the algorithms from `sub_sorts`, defaulting to InsertionSort.
+fn sub_sort_parameter(name: &'static str) -> Parameter {
+    Parameter {
+        name,
+        kind: ParamKind::Choice {
+            options: sub_sorts()
+                .iter()
+                .map(|algorithm| algorithm.label())
+                .collect(),
+            names: sub_sorts()
+                .iter()
+                .map(|algorithm| format!("{:?}", algorithm))
+                .collect(),
+        },
+        default: ParamValue::Choice(
+            sub_sorts()
+                .iter()
+                .position(|&algorithm| algorithm == Algorithms::Insertion)
+                .unwrap_or(0),
+        ),
+    }
+}
+
+/// Declares the tunable parameters of an algorithm, in the order its constructor takes them.
+pub fn parameters(algorithm: Algorithms) -> Vec<Parameter> {
+    match algorithm {
+        Algorithms::Quick => vec![
+            Parameter {
+                name: "Pivot",
+                kind: options::<PivotStrategy>(),
+                default: ParamValue::Choice(PivotStrategy::Last as usize),
+            },
+            Parameter {
+                name: "Partition",
+                kind: options::<PartitionScheme>(),
+                default: ParamValue::Choice(PartitionScheme::Lomuto as usize),
+            },
+        ],
+        Algorithms::Bucket | Algorithms::Sample => vec![
+            Parameter {
+                name: "Buckets",
+                kind: ParamKind::Integer { min: 2, max: 32 },
+                default: ParamValue::Integer(8),
+            },
+            sub_sort_parameter("Each bucket"),
+        ],
+        Algorithms::Shell => vec![Parameter {
+            name: "Gaps",
+            kind: options::<GapSequence>(),
+            default: ParamValue::Choice(GapSequence::Shell as usize),
+        }],
+        Algorithms::Comb => vec![Parameter {
+            name: "Shrink factor",
+            kind: ParamKind::Decimal {
+                min: 1.05,
+                max: 3.0,
+            },
+            default: ParamValue::Decimal(DEFAULT_SHRINK),
+        }],
+        Algorithms::Intro => vec![Parameter {
+            name: "Insertion cutoff",
+            kind: ParamKind::Integer { min: 1, max: 64 },
+            default: ParamValue::Integer(DEFAULT_INSERTION_THRESHOLD),
+        }],
+        Algorithms::BlockMerge => vec![Parameter {
+            name: "Insertion run",
+            kind: ParamKind::Integer { min: 1, max: 64 },
+            default: ParamValue::Integer(DEFAULT_INSERTION_RUN),
+        }],
+        Algorithms::Hybrid => vec![
+            Parameter {
+                name: "Outer",
+                kind: options::<Outer>(),
+                default: ParamValue::Choice(Outer::QuickSort as usize),
+            },
+            Parameter {
+                name: "Cutoff",
+                kind: ParamKind::Integer { min: 2, max: 64 },
+                default: ParamValue::Integer(DEFAULT_CUTOFF),
+            },
+            sub_sort_parameter("Short ranges"),
+        ],
+        Algorithms::QuickSelect | Algorithms::IntroSelect => vec![Parameter {
+            name: "Target rank %",
+            kind: ParamKind::Integer { min: 0, max: 100 },
+            default: ParamValue::Integer(DEFAULT_TARGET_PERCENT),
+        }],
+        Algorithms::HeapTopK | Algorithms::PartialInsertion => vec![Parameter {
+            name: "k %",
+            kind: ParamKind::Integer { min: 1, max: 100 },
+            default: ParamValue::Integer(DEFAULT_K_PERCENT),
+        }],
+        _ => Vec::new(),
+    }
+}
+
+/// Converts an algorithm's parameter values to the form saved in files.
+pub fn save_values(algorithm: Algorithms, values: &[ParamValue]) -> Vec<SavedValue> {
+    parameters(algorithm)
+        .iter()
+        .zip(values)
+        .map(|(parameter, &value)| parameter.save(value))
+        .collect()
+}
+
+/// Reads an algorithm's parameter values saved by `save_values`.
+/// # Returns
+/// The values, or a description of the first one that does not fit its parameter.
+pub fn load_values(algorithm: Algorithms, saved: &[SavedValue]) -> Result<Vec<ParamValue>, String> {
+    let parameters = parameters(algorithm);
+    if saved.len() != parameters.len() {
+        return Err(format!(
+            "{:?} takes {} parameters, got {}",
+            algorithm,
+            parameters.len(),
+            saved.len()
+        ));
+    }
+    parameters
+        .iter()
+        .zip(saved)
+        .map(|(parameter, saved)| parameter.load(saved))
+        .collect()
+}
+
+/// The parameter values chosen for every algorithm.
+/// Algorithms whose values were never changed use their declared defaults.
+#[derive(Default, Clone)]
+pub struct Settings {
+    values: HashMap<Algorithms, Vec<ParamValue>>,
+}
+
+impl Settings {
+    /// Returns the values of an algorithm's parameters, in declaration order.
+    pub fn values(&self, algorithm: Algorithms) -> Vec<ParamValue> {
+        self.values.get(&algorithm).cloned().unwrap_or_else(|| {
+            parameters(algorithm)
+                .iter()
+                .map(|parameter| parameter.default)
+                .collect()
+        })
+    }
+
+    /// Creates a sorter for `algorithm`, configured with its current parameter values.
+    pub fn create_sorter(&self, algorithm: Algorithms) -> Box<dyn Sorter> {
+        let values = self.values(algorithm);
+        let value = |index: usize| values[index];
+        match algorithm {
+            Algorithms::Bubble => Box::new(BubbleSort::new()),
+            Algorithms::Selection => Box::new(SelectionSort::new()),
+            Algorithms::Insertion => Box::new(InsertionSort::new()),
+            Algorithms::Merge => Box::new(MergeSort::new()),
+            Algorithms::Bogo => Box::new(BogoSort::new()),
+            Algorithms::Heap => Box::new(HeapSort::new()),
+            Algorithms::Quick => Box::new(QuickSort::with_options(
+                value(0).choice(),
+                value(1).choice(),
+            )),
+            Algorithms::Counting => Box::new(CountingSort::new()),
+            Algorithms::Cocktail => Box::new(CocktailSort::new()),
+            Algorithms::Gnome => Box::new(GnomeSort::new()),
+            Algorithms::Pancake => Box::new(PancakeSort::new()),
+            Algorithms::Shell => Box::new(ShellSort::with_sequence(value(0).choice())),
+            Algorithms::Comb => Box::new(CombSort::with_shrink(value(0).decimal())),
+            Algorithms::OddEven => Box::new(OddEvenSort::new()),
+            Algorithms::Intro => Box::new(IntroSort::with_threshold(value(0).integer())),
+            Algorithms::Pdq => Box::new(PdqSort::new()),
+            Algorithms::Bitonic => Box::new(BitonicSort::new()),
+            Algorithms::OddEvenMerge => Box::new(OddEvenMergeSort::new()),
+            Algorithms::Cycle => Box::new(CycleSort::new()),
+            Algorithms::Smooth => Box::new(SmoothSort::new()),
+            Algorithms::Bucket | Algorithms::Sample => {
+                let splitters = if algorithm == Algorithms::Bucket {
+                    Splitters::EqualWidth
+                } else {
+                    Splitters::Sampled
+                };
+                let sub_sorts = sub_sorts();
+                let sub_sort = sub_sorts
+                    .get(value(1).integer())
+                    .copied()
+                    .unwrap_or(Algorithms::Insertion);
+                Box::new(BucketSort::with_options(
+                    splitters,
+                    value(0).integer(),
+                    self.create_sorter(sub_sort),
+                ))
+            }
+            Algorithms::Tree => Box::new(TreeSort::new()),
+            Algorithms::Patience => Box::new(PatienceSort::new()),
+            Algorithms::RotationMerge => Box::new(RotationMergeSort::new()),
+            Algorithms::BlockMerge => Box::new(BlockMergeSort::with_run(value(0).integer())),
+            Algorithms::TopDownMerge => Box::new(TopDownMergeSort::new()),
+            Algorithms::Hybrid => {
+                let inner = sub_sorts()
+                    .get(value(2).integer())
+                    .copied()
+                    .unwrap_or(Algorithms::Insertion);
+                Box::new(HybridSort::with_options(
+                    value(0).choice(),
+                    value(1).integer(),
+                    self.create_sorter(inner),
+                    &inner.label(),
+                ))
+            }
+            Algorithms::Stooge => Box::new(StoogeSort::new()),
+            Algorithms::Slow => Box::new(SlowSort::new()),
+            Algorithms::Permutation => Box::new(PermutationSort::new()),
+            Algorithms::QuickSelect | Algorithms::IntroSelect => Box::new(
+                QuickSelect::with_options(value(0).integer(), algorithm == Algorithms::IntroSelect),
+            ),
+            Algorithms::HeapTopK => Box::new(HeapTopK::with_percent(value(0).integer())),
+            Algorithms::PartialInsertion => {
+                Box::new(PartialInsertionSort::with_percent(value(0).integer()))
+            }
+        }
+    }
+
+    /// Stores new values for an algorithm's parameters.
+    pub fn set_values(&mut self, algorithm: Algorithms, values: Vec<ParamValue>) {
+        self.values.insert(algorithm, values);
+    }
+
+    /// Describes an algorithm's parameter values, such as `"Pivot=Random; Partition=Hoare"`.
+    /// Returns an empty string for algorithms without parameters.
+    pub fn describe(&self, algorithm: Algorithms) -> String {
+        parameters(algorithm)
+            .iter()
+            .zip(self.values(algorithm))
+            .map(|(parameter, value)| {
+                let shown = match (&parameter.kind, value) {
+                    (ParamKind::Choice { options, .. }, ParamValue::Choice(index)) => {
+                        options.get(index).cloned().unwrap_or_default()
+                    }
+                    (_, ParamValue::Decimal(value)) => format!("{:.2}", value),
+                    (_, value) => value.integer().to_string(),
+                };
+                format!("{}={}", parameter.name, shown)
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
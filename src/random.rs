@@ -1,4 +1,5 @@
-use rand::{distributions::Uniform, Rng};
+use crate::types::Distribution;
+use rand::{distributions::Uniform, seq::SliceRandom, Rng};
 
 /// Generates a random vector of integers within a specified range.
 /// # Arguments
@@ -7,8 +8,54 @@ use rand::{distributions::Uniform, Rng};
 /// * `n` - The number of integers to generate.
 /// # Returns
 /// A `Vec<usize>` containing `n` random integers in the range `[floor, ceil)`.
-
 pub fn gen_random_vector(floor: usize, ceil: usize, n: usize) -> Vec<usize> {
     let range = Uniform::new(floor, ceil);
     rand::thread_rng().sample_iter(&range).take(n).collect()
 }
+
+/// Generates a vector of integers shaped by the given distribution.
+/// # Arguments
+/// * `distribution` - The shape of the generated data.
+/// * `floor` - The minimum value (inclusive) for the generated integers.
+/// * `ceil` - The maximum value (exclusive) for the generated integers.
+/// * `n` - The number of integers to generate.
+/// # Returns
+/// A `Vec<usize>` containing `n` integers in the range `[floor, ceil)`.
+pub fn gen_vector(distribution: Distribution, floor: usize, ceil: usize, n: usize) -> Vec<usize> {
+    let mut rng = rand::thread_rng();
+    let mut numbers = gen_random_vector(floor, ceil, n);
+
+    match distribution {
+        Distribution::Random => {}
+        Distribution::Sorted => numbers.sort(),
+        Distribution::Reversed => numbers.sort_by(|a, b| b.cmp(a)),
+        Distribution::NearlySorted => {
+            // Sort, then disturb roughly one element in twenty with a nearby swap.
+            numbers.sort();
+            for _ in 0..(n / 20).max(1) {
+                if n < 2 {
+                    break;
+                }
+                let i = rng.gen_range(0..n - 1);
+                let j = (i + rng.gen_range(1..=3)).min(n - 1);
+                numbers.swap(i, j);
+            }
+        }
+        Distribution::FewUnique => {
+            // Draw every element from a handful of distinct values.
+            let values = gen_random_vector(floor, ceil, 4);
+            for number in numbers.iter_mut() {
+                *number = *values.choose(&mut rng).unwrap_or(&floor);
+            }
+        }
+        Distribution::Skewed => {
+            // Cube a uniform sample so most values crowd near the floor.
+            for number in numbers.iter_mut() {
+                let t: f64 = rng.gen::<f64>().powi(3);
+                *number = floor + (t * (ceil - floor) as f64) as usize;
+            }
+        }
+    }
+
+    numbers
+}
@@ -0,0 +1,27 @@
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use std::cell::RefCell;
+
+thread_local! {
+    /// The generator `with_rng` hands out while `seeded` runs on this thread, if any.
+    static SEEDED: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with every random choice of the algorithms drawn from a generator seeded with
+/// `seed`, restoring the previous generator afterwards.
+/// Used by traces and tests, whose runs must be reproducible.
+pub fn seeded<R>(seed: u64, f: impl FnOnce() -> R) -> R {
+    let previous = SEEDED.with(|rng| rng.replace(Some(StdRng::seed_from_u64(seed))));
+    let result = f();
+    SEEDED.with(|rng| rng.replace(previous));
+    result
+}
+
+/// Calls `f` with the generator algorithms draw their random choices from:
+/// the seeded one inside `seeded`, otherwise the thread's own generator.
+pub fn with_rng<R>(f: impl FnOnce(&mut dyn RngCore) -> R) -> R {
+    SEEDED.with(|seeded| match seeded.borrow_mut().as_mut() {
+        Some(rng) => f(rng),
+        None => f(&mut rand::thread_rng()),
+    })
+}
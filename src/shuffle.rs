@@ -0,0 +1,205 @@
+use crate::algorithms::{Reasons, Sorter};
+use crate::rng;
+use crate::sound::{self, play_beep};
+use rand::Rng;
+use strum_macros::EnumIter;
+
+/// How a shuffle rearranges the array.
+#[derive(PartialEq, Debug, EnumIter, Clone, Copy)]
+pub enum ShuffleMethod {
+    /// Fisher-Yates: every position from the back swaps with a random position at or
+    /// before it, so every permutation is equally likely.
+    FisherYates,
+    /// Every position swaps with a random position anywhere in the array. Its n^n equally
+    /// likely swap sequences cannot spread evenly over the n! permutations, so it is biased.
+    Naive,
+    /// Every element gets a random key and the elements are sorted by key,
+    /// here with a selection sort that moves one element per step.
+    RandomKey,
+}
+
+impl ShuffleMethod {
+    /// Returns the name shown for the method.
+    pub fn label(self) -> &'static str {
+        match self {
+            ShuffleMethod::FisherYates => "Fisher-Yates",
+            ShuffleMethod::Naive => "Naive (biased)",
+            ShuffleMethod::RandomKey => "Sort by random key",
+        }
+    }
+}
+
+/// A shuffle run step by step, so it can be animated like a sorter.
+/// Each step performs a single swap.
+pub struct Shuffle {
+    method: ShuffleMethod,   // How the array is rearranged.
+    next: usize,             // Number of steps taken so far.
+    keys: Vec<u32>,          // Random keys of the elements, for `RandomKey`.
+    started: bool,           // Whether the keys have been drawn.
+    special: (usize, usize), // Indices swapped by the last step.
+    finished: bool,          // Indicates whether the shuffle is complete.
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+}
+
+impl Shuffle {
+    /// Creates a shuffle using `method`.
+    pub fn with_method(method: ShuffleMethod) -> Self {
+        Shuffle {
+            method,
+            next: 0,
+            keys: Vec::new(),
+            started: false,
+            special: (usize::MAX, usize::MAX),
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+
+    /// Returns the index whose key is the smallest from `start` on.
+    fn smallest_key(&mut self, start: usize) -> usize {
+        let mut smallest = start;
+        for i in start + 1..self.keys.len() {
+            self.comparisons += 1;
+            if self.keys[i] < self.keys[smallest] {
+                smallest = i;
+            }
+        }
+        smallest
+    }
+}
+
+impl Sorter for Shuffle {
+    /// Creates a Fisher-Yates shuffle.
+    fn new() -> Self {
+        Self::with_method(ShuffleMethod::FisherYates)
+    }
+
+    /// Returns the indices swapped by the last step.
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    /// Every step of a shuffle is a swap.
+    fn reason(&self) -> Reasons {
+        Reasons::Switching
+    }
+
+    /// Executes a single step of the shuffle, swapping one pair of elements.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+        let n = array.len();
+        if !self.started {
+            self.started = true;
+            if self.method == ShuffleMethod::RandomKey {
+                self.keys = rng::with_rng(|rng| (0..n).map(|_| rng.gen()).collect());
+            }
+        }
+
+        let total = match self.method {
+            ShuffleMethod::Naive => n,
+            ShuffleMethod::FisherYates | ShuffleMethod::RandomKey => n.saturating_sub(1),
+        };
+        if self.next >= total {
+            self.finished = true;
+            self.special = (usize::MAX, usize::MAX);
+            return true;
+        }
+
+        let (i, j) = match self.method {
+            ShuffleMethod::FisherYates => {
+                let i = n - 1 - self.next;
+                (i, rng::with_rng(|rng| rng.gen_range(0..=i)))
+            }
+            ShuffleMethod::Naive => (self.next, rng::with_rng(|rng| rng.gen_range(0..n))),
+            ShuffleMethod::RandomKey => {
+                let smallest = self.smallest_key(self.next);
+                self.keys.swap(self.next, smallest);
+                (self.next, smallest)
+            }
+        };
+        if i != j {
+            array.swap(i, j);
+            self.swaps += 1;
+            self.writes += 2;
+            play_beep();
+        }
+        self.special = (i, j);
+        self.next += 1;
+        false
+    }
+
+    /// Resets the state for a fresh shuffle, keeping the method.
+    fn reset_state(&mut self) {
+        *self = Self::with_method(self.method);
+    }
+
+    /// Checks if the shuffle is complete.
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+}
+
+/// How often each value ended up at each position over many shuffles of `0..size`.
+pub struct ShuffleStats {
+    pub method: ShuffleMethod,   // The shuffle that was measured.
+    pub size: usize,             // Length of the shuffled array.
+    pub trials: usize,           // Number of shuffles run.
+    pub counts: Vec<Vec<usize>>, // `counts[position][value]`.
+}
+
+impl ShuffleStats {
+    /// Shuffles `0..size` `trials` times with `method`, silently,
+    /// and counts how often each value lands at each position.
+    pub fn run(method: ShuffleMethod, size: usize, trials: usize) -> Self {
+        let mut counts = vec![vec![0; size]; size];
+        sound::muted(|| {
+            for _ in 0..trials {
+                let mut array: Vec<usize> = (0..size).collect();
+                let mut shuffle = Shuffle::with_method(method);
+                while !shuffle.step(&mut array) {}
+                for (position, &value) in array.iter().enumerate() {
+                    counts[position][value] += 1;
+                }
+            }
+        });
+        ShuffleStats {
+            method,
+            size,
+            trials,
+            counts,
+        }
+    }
+
+    /// Returns how often each cell would be hit by an unbiased shuffle.
+    pub fn expected(&self) -> f64 {
+        self.trials as f64 / self.size.max(1) as f64
+    }
+
+    /// Returns the largest relative deviation of any cell from the expected count.
+    pub fn max_deviation(&self) -> f64 {
+        let expected = self.expected();
+        self.counts
+            .iter()
+            .flatten()
+            .map(|&count| (count as f64 - expected).abs() / expected)
+            .fold(0.0, f64::max)
+    }
+}
@@ -1,7 +1,17 @@
 use rodio::{source::SineWave, OutputStream, Sink, Source};
+use std::cell::Cell;
 use std::time::Duration;
 
+thread_local! {
+    /// Whether `play_beep` is currently silenced on this thread.
+    static MUTED: Cell<bool> = const { Cell::new(false) };
+}
+
 pub fn play_beep() {
+    if MUTED.with(Cell::get) {
+        return;
+    }
+
     // Default system beep
     print!("\x07");
 
@@ -17,3 +27,35 @@ pub fn play_beep() {
         sink.sleep_until_end(); // Ensure sound finishes
     }
 }
+
+/// Runs `f` with `play_beep` silenced on the calling thread, restoring the previous setting
+/// afterwards. Other threads, such as the visualizer's sorting worker, keep their sound.
+/// Used when algorithms run without the UI, where each blocking beep would stall the run.
+/// The setting is also restored if `f` panics, so harnesses that catch panics on the
+/// same thread keep their sound.
+pub fn muted<R>(f: impl FnOnce() -> R) -> R {
+    /// Restores the setting `muted` replaced when dropped, also while unwinding.
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            MUTED.with(|muted| muted.set(self.0));
+        }
+    }
+
+    let _restore = Restore(MUTED.with(|muted| muted.replace(true)));
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic;
+
+    #[test]
+    fn a_panic_does_not_leave_the_thread_muted() {
+        let result = panic::catch_unwind(|| muted(|| panic!("the sort failed")));
+        assert!(result.is_err());
+        assert!(!MUTED.with(Cell::get));
+    }
+}
@@ -0,0 +1,550 @@
+//! Trace files: a complete run of an algorithm saved as versioned JSON, with everything
+//! needed to replay it step by step without the algorithm itself.
+//!
+//! A trace holds the algorithm, its parameters, the seed of its random choices, the input,
+//! and one event per step:
+//!
+//! ```json
+//! {"version":2,"algorithm":"Bubble","parameters":[],"seed":7,"input":[3,1,2],
+//!  "events":[{"special":[0,1],"reason":"Comparing","changes":[[0,1],[1,3]],
+//!             "comparisons":1,"swaps":1,"writes":2}, ...]}
+//! ```
+//!
+//! `special` is the pair of indices the step highlights, `null` for none. `changes` lists
+//! the `[index, value]` writes the step made to the array, and the counters are totals so far.
+//! `parameters` holds the algorithm's parameter values in declaration order, with choices
+//! saved by option name, such as `[{"Integer":8},{"Choice":"Insertion"}]`. A value outside
+//! its parameter's range rejects the whole trace.
+//!
+//! # External traces
+//!
+//! Sorts written in other languages can be visualized by printing one JSON operation per line
+//! (NDJSON) and opening the output as a `.ndjson` or `.jsonl` file, or piping it to
+//! `sorting-visualizer --trace -`. The first operation gives the array, every later one is
+//! replayed as one step:
+//!
+//! ```text
+//! {"op":"init","array":[5,3,8,1],"name":"my_sort.py"}
+//! {"op":"compare","i":0,"j":1}
+//! {"op":"swap","i":0,"j":1}
+//! {"op":"set","i":2,"value":4}
+//! ```
+//!
+//! * `init` - The initial `array`, required on the first line. `name` is optional.
+//! * `compare` - Elements `i` and `j` are compared. Counts one comparison.
+//! * `swap` - Elements `i` and `j` trade places. Counts one swap and two writes.
+//! * `set` - `value` is written to element `i`. Counts one write.
+//!
+//! Blank lines are skipped. The first line that is not valid JSON, names an unknown
+//! operation or an index outside the array is reported with its line number.
+
+use crate::algorithms::{input_limit, Reasons, Sorter};
+use crate::params::{self, ParamValue, SavedValue, Settings};
+use crate::rng;
+use crate::sound::{self, play_beep};
+use crate::types::Algorithms;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+use std::path::Path;
+use strum::IntoEnumIterator;
+
+/// Version of the trace format written by `Trace::to_json`.
+pub const TRACE_VERSION: u32 = 2;
+
+/// Maximum number of steps recorded in a trace before the run is cut off.
+pub const MAX_TRACE_STEPS: usize = 200_000;
+
+/// A recorded run, see the module documentation for the file format.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Trace {
+    pub version: u32,
+    pub algorithm: String, // Name of the algorithm as in `Algorithms`, or of whatever produced the trace.
+    #[serde(default)]
+    pub parameters: Vec<SavedValue>, // Parameter values of the algorithm, empty if unknown.
+    #[serde(default)]
+    pub seed: Option<u64>, // Seed of the algorithm's random choices, if it made any.
+    pub input: Vec<usize>,
+    pub events: Vec<Event>,
+}
+
+/// An operation of an external trace, see the module documentation.
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum Operation {
+    Init {
+        array: Vec<usize>,
+        name: Option<String>,
+    },
+    Compare {
+        i: usize,
+        j: usize,
+    },
+    Swap {
+        i: usize,
+        j: usize,
+    },
+    Set {
+        i: usize,
+        value: usize,
+    },
+}
+
+/// What a single step did.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Event {
+    pub special: [Option<usize>; 2], // The indices the step highlights, `None` for none.
+    pub reason: Reasons,
+    #[serde(default)]
+    pub changes: Vec<(usize, usize)>, // The `(index, value)` writes the step made.
+    pub comparisons: usize, // Totals after the step.
+    pub swaps: usize,
+    pub writes: usize,
+}
+
+impl Event {
+    /// Describes the step `sorter` just took from `before` to `after`.
+    pub fn observe(sorter: &dyn Sorter, before: &[usize], after: &[usize]) -> Self {
+        let (a, b) = sorter.special();
+        let index = |i: usize| (i < after.len()).then_some(i);
+        Event {
+            special: [index(a), index(b)],
+            reason: sorter.reason(),
+            changes: (0..after.len())
+                .filter(|&i| before.get(i) != Some(&after[i]))
+                .map(|i| (i, after[i]))
+                .collect(),
+            comparisons: sorter.comparisons(),
+            swaps: sorter.swaps(),
+            writes: sorter.writes(),
+        }
+    }
+}
+
+impl Trace {
+    /// Runs `algorithm` headless on `input`, drawing its random choices from `seed`,
+    /// and records every step.
+    /// # Arguments
+    /// * `settings` - The parameters the algorithm runs with.
+    /// * `input` - The array to sort, capped like in the visualizer for very slow algorithms.
+    pub fn record(algorithm: Algorithms, settings: &Settings, seed: u64, input: &[usize]) -> Self {
+        let mut sorter = settings.create_sorter(algorithm);
+        let input = &input[..input_limit(sorter.as_ref(), input.len())];
+        let mut array = input.to_vec();
+        let mut events = Vec::new();
+        rng::seeded(seed, || {
+            sound::muted(|| {
+                while events.len() < MAX_TRACE_STEPS {
+                    let before = array.clone();
+                    let finished = sorter.step(&mut array);
+                    events.push(Event::observe(sorter.as_ref(), &before, &array));
+                    if finished {
+                        break;
+                    }
+                }
+            })
+        });
+        Trace {
+            version: TRACE_VERSION,
+            algorithm: format!("{:?}", algorithm),
+            parameters: params::save_values(algorithm, &settings.values(algorithm)),
+            seed: Some(seed),
+            input: input.to_vec(),
+            events,
+        }
+    }
+
+    /// Writes the trace as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a trace always serializes")
+    }
+
+    /// Reads a trace from JSON and checks that it can be replayed.
+    /// # Returns
+    /// The trace, or a description of the first problem found.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let trace: Trace = serde_json::from_str(json).map_err(|error| error.to_string())?;
+        if trace.version != TRACE_VERSION {
+            return Err(format!(
+                "unsupported trace version {}, expected {}",
+                trace.version, TRACE_VERSION
+            ));
+        }
+        if let Some(algorithm) = trace.known_algorithm() {
+            if !trace.parameters.is_empty() {
+                params::load_values(algorithm, &trace.parameters)?;
+            }
+        }
+        let len = trace.input.len();
+        for (step, event) in trace.events.iter().enumerate() {
+            let indices = event.special.iter().flatten();
+            let changed = event.changes.iter().map(|(index, _)| index);
+            if let Some(index) = indices.chain(changed).find(|&&index| index >= len) {
+                return Err(format!(
+                    "step {}: index {} is out of bounds for {} elements",
+                    step + 1,
+                    index,
+                    len
+                ));
+            }
+        }
+        Ok(trace)
+    }
+
+    /// Reads the operations of an external program, one JSON object per line, and turns
+    /// them into a trace of one step per operation, checking them against the array.
+    /// # Returns
+    /// The trace, or a description of the first illegal operation with its line number.
+    pub fn from_ndjson(ndjson: &str) -> Result<Self, String> {
+        let mut lines = ndjson
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+        let parse = |number: usize, line: &str| {
+            serde_json::from_str::<Operation>(line).map_err(|error| {
+                // Every line is parsed on its own, so only the column is worth reporting.
+                let message = error.to_string();
+                let message = message.split(" at line ").next().unwrap_or_default();
+                format!("line {}, column {}: {}", number, error.column(), message)
+            })
+        };
+
+        let (mut array, name) = match lines.next() {
+            Some((number, line)) => match parse(number, line)? {
+                Operation::Init { array, name } => (array, name),
+                _ => return Err(format!("line {}: the first operation must be init", number)),
+            },
+            None => return Err("no operations, the first one must be init".to_string()),
+        };
+        let input = array.clone();
+        let (mut comparisons, mut swaps, mut writes) = (0, 0, 0);
+        let mut events = Vec::new();
+        for (number, line) in lines {
+            let operation = parse(number, line)?;
+            let len = array.len();
+            let check = |index: usize| {
+                if index < len {
+                    Ok(index)
+                } else {
+                    Err(format!(
+                        "line {}: index {} is out of bounds for {} elements",
+                        number, index, len
+                    ))
+                }
+            };
+            let (special, reason, changes) = match operation {
+                Operation::Init { .. } => {
+                    return Err(format!(
+                        "line {}: init may only be the first operation",
+                        number
+                    ))
+                }
+                Operation::Compare { i, j } => {
+                    let (i, j) = (check(i)?, check(j)?);
+                    comparisons += 1;
+                    ([Some(i), Some(j)], Reasons::Comparing, Vec::new())
+                }
+                Operation::Swap { i, j } => {
+                    let (i, j) = (check(i)?, check(j)?);
+                    array.swap(i, j);
+                    swaps += 1;
+                    writes += 2;
+                    let changes = vec![(i, array[i]), (j, array[j])];
+                    ([Some(i), Some(j)], Reasons::Switching, changes)
+                }
+                Operation::Set { i, value } => {
+                    let i = check(i)?;
+                    array[i] = value;
+                    writes += 1;
+                    ([Some(i), None], Reasons::Switching, vec![(i, value)])
+                }
+            };
+            events.push(Event {
+                special,
+                reason,
+                changes,
+                comparisons,
+                swaps,
+                writes,
+            });
+        }
+        Ok(Trace {
+            version: TRACE_VERSION,
+            algorithm: name.unwrap_or_else(|| "External program".to_string()),
+            parameters: Vec::new(),
+            seed: None,
+            input,
+            events,
+        })
+    }
+
+    /// Reads a trace from `path`, or operations from standard input if `path` is `-`.
+    /// Files ending in `.ndjson` or `.jsonl` hold operations of an external program,
+    /// any other file a trace saved by the visualizer.
+    /// # Returns
+    /// The trace, or a description of why it cannot be read or replayed.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if path == Path::new("-") {
+            let mut ndjson = String::new();
+            io::stdin()
+                .read_to_string(&mut ndjson)
+                .map_err(|error| error.to_string())?;
+            return Self::from_ndjson(&ndjson);
+        }
+        let text = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("ndjson" | "jsonl") => Self::from_ndjson(&text),
+            _ => Self::from_json(&text),
+        }
+    }
+
+    /// Returns the algorithm the trace was recorded with, if it is one of ours.
+    pub fn known_algorithm(&self) -> Option<Algorithms> {
+        Algorithms::iter().find(|algorithm| format!("{:?}", algorithm) == self.algorithm)
+    }
+
+    /// Returns the parameters the trace was recorded with, if they fit `algorithm`.
+    pub fn known_parameters(&self, algorithm: Algorithms) -> Option<Vec<ParamValue>> {
+        params::load_values(algorithm, &self.parameters).ok()
+    }
+}
+
+/// Replays a trace as a sorter: every step applies the next recorded event to the array,
+/// so the visualizer shows the run with the usual bars, counters and sound.
+pub struct TracePlayer {
+    trace: Trace,
+    next: usize,             // Index of the next event to apply.
+    special: (usize, usize), // Indices highlighted by the last event.
+    reason: Reasons,         // Reason of the last event.
+    finished: bool,          // Indicates whether every event has been applied.
+    comparisons: usize,
+    swaps: usize,
+    writes: usize,
+}
+
+impl TracePlayer {
+    /// Creates a player of `trace`, which must have been checked by `Trace::from_json`
+    /// or come from `Trace::record`.
+    pub fn with_trace(trace: Trace) -> Self {
+        TracePlayer {
+            trace,
+            next: 0,
+            special: (usize::MAX, usize::MAX),
+            reason: Reasons::Comparing,
+            finished: false,
+            comparisons: 0,
+            swaps: 0,
+            writes: 0,
+        }
+    }
+}
+
+impl Sorter for TracePlayer {
+    /// Creates a player of an empty trace, which finishes on its first step.
+    fn new() -> Self {
+        Self::with_trace(Trace {
+            version: TRACE_VERSION,
+            algorithm: String::new(),
+            parameters: Vec::new(),
+            seed: None,
+            input: Vec::new(),
+            events: Vec::new(),
+        })
+    }
+
+    fn special(&self) -> (usize, usize) {
+        self.special
+    }
+
+    fn reason(&self) -> Reasons {
+        self.reason
+    }
+
+    /// Applies the next event of the trace to `array`.
+    fn step(&mut self, array: &mut Vec<usize>) -> bool {
+        if self.finished {
+            return true;
+        }
+        if let Some(event) = self.trace.events.get(self.next) {
+            let [a, b] = event.special;
+            let len = array.len();
+            // The array may have been edited since the trace was loaded, so stay in bounds.
+            let index = |i: Option<usize>| i.filter(|&i| i < len).unwrap_or(usize::MAX);
+            self.special = (index(a), index(b));
+            self.reason = event.reason;
+            for &(index, value) in &event.changes {
+                if let Some(slot) = array.get_mut(index) {
+                    *slot = value;
+                }
+            }
+            self.comparisons = event.comparisons;
+            self.swaps = event.swaps;
+            self.writes = event.writes;
+            play_beep();
+        } else {
+            self.special = (usize::MAX, usize::MAX);
+        }
+        self.next += 1;
+        self.finished = self.next >= self.trace.events.len();
+        self.finished
+    }
+
+    fn reset_state(&mut self) {
+        self.next = 0;
+        self.special = (usize::MAX, usize::MAX);
+        self.reason = Reasons::Comparing;
+        self.finished = false;
+        self.comparisons = 0;
+        self.swaps = 0;
+        self.writes = 0;
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    fn swaps(&self) -> usize {
+        self.swaps
+    }
+
+    fn writes(&self) -> usize {
+        self.writes
+    }
+
+    fn phase(&self) -> Option<&'static str> {
+        Some("Replaying trace")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_matches_the_recorded_run() {
+        let settings = Settings::default();
+        let input = [5, 3, 8, 1, 9, 2, 7, 3];
+        for algorithm in [Algorithms::Quick, Algorithms::Bogo, Algorithms::Shell] {
+            let trace = Trace::record(algorithm, &settings, 47, &input);
+            let loaded = Trace::from_json(&trace.to_json()).unwrap();
+            assert_eq!(loaded, trace);
+
+            // The replay must go through the same states as a run of the algorithm itself.
+            let mut sorter = settings.create_sorter(algorithm);
+            let mut player = TracePlayer::with_trace(loaded);
+            let (mut expected, mut array) = (trace.input.clone(), trace.input.clone());
+            rng::seeded(47, || {
+                sound::muted(|| loop {
+                    let finished = sorter.step(&mut expected);
+                    assert_eq!(player.step(&mut array), finished);
+                    assert_eq!(array, expected, "{algorithm:?}");
+                    assert_eq!(player.special(), sorter.special());
+                    assert_eq!(player.reason(), sorter.reason());
+                    assert_eq!(
+                        (player.comparisons(), player.swaps(), player.writes()),
+                        (sorter.comparisons(), sorter.swaps(), sorter.writes())
+                    );
+                    if finished {
+                        break;
+                    }
+                })
+            });
+            player.reset_state();
+            assert!(!player.is_finished());
+            assert_eq!(player.comparisons(), 0);
+        }
+    }
+
+    #[test]
+    fn external_operations_become_steps() {
+        let ndjson = r#"
+            {"op":"init","array":[3,1,2],"name":"student.c"}
+            {"op":"compare","i":0,"j":1}
+            {"op":"swap","i":0,"j":1}
+
+            {"op":"set","i":2,"value":3}
+            {"op":"set","i":1,"value":2}
+        "#;
+        let trace = Trace::from_ndjson(ndjson).unwrap();
+        assert_eq!(trace.algorithm, "student.c");
+        assert_eq!(trace.input, [3, 1, 2]);
+        assert_eq!(trace.events.len(), 4);
+
+        let mut player = TracePlayer::with_trace(trace.clone());
+        let mut array = trace.input.clone();
+        let steps = sound::muted(|| (1..).find(|_| player.step(&mut array)).unwrap());
+        assert_eq!(steps, 4);
+        assert_eq!(array, [1, 2, 3]);
+        assert_eq!(
+            (player.comparisons(), player.swaps(), player.writes()),
+            (1, 1, 4)
+        );
+        assert_eq!(player.special(), (1, usize::MAX));
+    }
+
+    #[test]
+    fn the_first_illegal_operation_is_reported() {
+        let init = r#"{"op":"init","array":[2,1]}"#;
+        let error = |ops: &str| Trace::from_ndjson(&format!("{init}\n{ops}")).unwrap_err();
+
+        assert_eq!(
+            error("{\"op\":\"compare\",\"i\":0,\"j\":1}\n{\"op\":\"swap\",\"i\":1,\"j\":2}"),
+            "line 3: index 2 is out of bounds for 2 elements"
+        );
+        assert!(error(r#"{"op":"shuffle"}"#).starts_with("line 2, column"));
+        assert!(error(r#"{"op":"swap","i":0}"#).contains("missing field `j`"));
+        assert!(error("swap 0 1").starts_with("line 2"));
+        assert_eq!(error(init), "line 2: init may only be the first operation");
+        assert_eq!(
+            Trace::from_ndjson(r#"{"op":"set","i":0,"value":1}"#).unwrap_err(),
+            "line 1: the first operation must be init"
+        );
+        assert!(Trace::from_ndjson("\n").is_err());
+    }
+
+    #[test]
+    fn invalid_traces_are_rejected() {
+        let mut trace = Trace::record(Algorithms::Bubble, &Settings::default(), 1, &[2, 1]);
+        trace.version = TRACE_VERSION + 1;
+        assert!(Trace::from_json(&trace.to_json())
+            .unwrap_err()
+            .contains("version"));
+
+        trace.version = TRACE_VERSION;
+        trace.events[0].changes.push((2, 5));
+        assert_eq!(
+            Trace::from_json(&trace.to_json()).unwrap_err(),
+            "step 1: index 2 is out of bounds for 2 elements"
+        );
+        assert!(Trace::from_json("{\"version\":1}").is_err());
+    }
+
+    #[test]
+    fn parameters_are_checked_against_their_range() {
+        let settings = Settings::default();
+        let mut trace = Trace::record(Algorithms::Bucket, &settings, 1, &[2, 1]);
+        assert!(trace.to_json().contains(r#"{"Choice":"Insertion"}"#));
+        assert_eq!(
+            trace.known_parameters(Algorithms::Bucket),
+            Some(settings.values(Algorithms::Bucket))
+        );
+
+        trace.parameters[0] = SavedValue::Integer(1_000_000_000_000);
+        assert_eq!(
+            Trace::from_json(&trace.to_json()).unwrap_err(),
+            "parameter Buckets: 1000000000000 is outside 2..=32"
+        );
+        trace.parameters[0] = SavedValue::Integer(8);
+        trace.parameters[1] = SavedValue::Choice("Sleep".to_string());
+        assert!(Trace::from_json(&trace.to_json())
+            .unwrap_err()
+            .contains("unknown option"));
+        trace.parameters.pop();
+        assert!(Trace::from_json(&trace.to_json()).is_err());
+        assert_eq!(trace.known_parameters(Algorithms::Bucket), None);
+    }
+}
@@ -2,7 +2,7 @@ use std::time::Duration;
 use strum_macros::EnumIter;
 
 /// Enum representing the available sorting algorithms.
-#[derive(PartialEq, Debug, EnumIter, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, EnumIter, Clone, Copy)]
 pub enum Algorithms {
     Bubble,
     Selection,
@@ -18,6 +18,72 @@ pub enum Algorithms {
     Shell,
     Comb,
     OddEven,
+    Intro,
+    Pdq,
+    Bitonic,
+    OddEvenMerge,
+    Cycle,
+    Smooth,
+    Bucket,
+    Sample,
+    Tree,
+    Patience,
+    RotationMerge,
+    BlockMerge,
+    TopDownMerge,
+    Hybrid,
+    Stooge,
+    Slow,
+    Permutation,
+    QuickSelect,
+    IntroSelect,
+    HeapTopK,
+    PartialInsertion,
+}
+
+impl Algorithms {
+    /// Returns the name shown for the algorithm, such as `"Quick Sort"`.
+    pub fn label(self) -> String {
+        match self {
+            Algorithms::QuickSelect => "Quickselect".to_string(),
+            Algorithms::IntroSelect => "Introselect".to_string(),
+            Algorithms::HeapTopK => "Heap Top-k".to_string(),
+            Algorithms::PartialInsertion => "Partial Insertion Sort".to_string(),
+            algorithm => format!("{:?} Sort", algorithm),
+        }
+    }
+
+    /// Returns whether the algorithm only finalizes some positions instead of sorting
+    /// the whole array, like a selection or top-k algorithm.
+    pub fn is_partial(self) -> bool {
+        matches!(
+            self,
+            Algorithms::QuickSelect
+                | Algorithms::IntroSelect
+                | Algorithms::HeapTopK
+                | Algorithms::PartialInsertion
+        )
+    }
+
+    /// Returns whether the algorithm needs so many steps that it declares an estimate
+    /// and has its input capped, like BogoSort or StoogeSort.
+    pub fn is_slow(self) -> bool {
+        matches!(
+            self,
+            Algorithms::Bogo | Algorithms::Stooge | Algorithms::Slow | Algorithms::Permutation
+        )
+    }
+}
+
+/// Enum representing the shape of the generated input data.
+#[derive(PartialEq, Debug, EnumIter, Clone, Copy)]
+pub enum Distribution {
+    Random,
+    Sorted,
+    Reversed,
+    NearlySorted,
+    FewUnique,
+    Skewed,
 }
 
 /// Enum representing the state of the visualizer.
@@ -1,37 +1,61 @@
 use super::constants::{CEIL, FLOOR, VECTOR_SIZE};
-use crate::random::gen_random_vector;
+use crate::random::gen_vector;
+use crate::shuffle::Shuffle;
 use crate::ui;
 use ui::*;
 
 pub struct ButtonHandler;
 
 impl ButtonHandler {
-    /// If not finished, takes a single step within the selected algorithm.
-    /// Else, resets the app state.
+    /// Starts sorting continuously from the current step.
+    pub(crate) fn handle_start(app: &mut Visualizer) {
+        app.state = State::Running;
+        app.start_time = Some(Instant::now());
+        app.session.run();
+    }
+
+    /// Pauses a running sort, which can go on with Start or Step.
+    pub(crate) fn handle_stop(app: &mut Visualizer) {
+        app.state = State::Start;
+        app.session.pause();
+    }
+
+    /// Asks the session for a single step within the selected algorithm.
+    /// Its outcome arrives as an update, which resets the app state once the sort is finished.
     pub(crate) fn handle_step(app: &mut Visualizer) {
-        if app.state != State::Finished && app.sorter.step(&mut app.numbers) {
-            app.state = State::Finished;
-        }
-        if app.state == State::Finished {
-            app.reset();
-        }
+        app.session.step();
     }
 
     /// Resets `app` state and sets `numbers` to their initial state.
     pub(crate) fn handle_reset(app: &mut Visualizer) {
         app.reset();
-        app.numbers = app.original_numbers.clone();
+        app.clear_spacetime();
+        app.set_input(app.original_numbers.clone());
         app.comparisons = 0;
         app.swaps = 0;
     }
 
-    /// Resets `app` state, generates new numbers, and updates the initial state.
+    /// Resets `app` state, generates new numbers shaped by the selected distribution,
+    /// and updates the initial state.
+    /// If a shuffle method is selected, the current numbers are shuffled step by step instead,
+    /// and become the initial state once the shuffle completes.
     pub(crate) fn handle_shuffle(app: &mut Visualizer) {
         app.reset();
-        app.numbers = gen_random_vector(FLOOR, CEIL, VECTOR_SIZE);
+        app.clear_spacetime();
+        if let Some(method) = app.selected_shuffle {
+            app.session.load(Box::new(Shuffle::with_method(method)));
+            app.shuffling = true;
+            ButtonHandler::handle_start(app);
+            return;
+        }
+        app.set_input(gen_vector(
+            app.selected_distribution,
+            FLOOR,
+            CEIL,
+            VECTOR_SIZE,
+        ));
         app.comparisons = 0;
         app.swaps = 0;
-        app.original_numbers = app.numbers.clone();
         app.user_input = app
             .numbers
             .iter()
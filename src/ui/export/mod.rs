@@ -0,0 +1,453 @@
+use super::constants::Theme;
+use super::scene::{self, Layout, Scene};
+use super::session::SorterView;
+use crate::algorithms::Sorter;
+use crate::sound;
+use eframe::{
+    egui::Align,
+    epaint::{pos2, Color32, Pos2, Rect, Shape},
+};
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Maximum number of steps an exported run may take before it is cut off.
+pub const MAX_EXPORT_STEPS: usize = 200_000;
+
+/// Time every frame of an animated GIF is shown, in hundredths of a second.
+const FRAME_DELAY: u16 = 4;
+
+/// Time the last frame of an animated GIF is shown before it loops, in hundredths of a second.
+const FINAL_DELAY: u16 = 200;
+
+/// File formats an animation can be exported to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum ExportFormat {
+    Gif,       // A single looping animated GIF.
+    PngFrames, // One numbered PNG per frame, in a folder.
+}
+
+impl ExportFormat {
+    /// Returns the name of the format shown in the export window.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Gif => "Animated GIF",
+            ExportFormat::PngFrames => "PNG frames",
+        }
+    }
+}
+
+/// How an animation is exported.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct ExportOptions {
+    pub format: ExportFormat,
+    pub every: usize, // A frame is rendered every `every` steps, plus the first and the last.
+    pub width: u16,   // Size of every frame in pixels.
+    pub height: u16,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::Gif,
+            every: 1,
+            width: 800,
+            height: 400,
+        }
+    }
+}
+
+/// An RGBA image drawn on the CPU, so exports need neither a window nor a GPU.
+pub(crate) struct Canvas {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>, // Row after row of red, green, blue and alpha bytes.
+}
+
+impl Canvas {
+    /// Creates a canvas filled with `background`.
+    pub fn new(width: u32, height: u32, background: Color32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: background
+                .to_array()
+                .repeat(width as usize * height as usize),
+        }
+    }
+
+    /// Draws a filled rectangle, line segment or circle. Other shapes, like text, are skipped.
+    pub fn draw(&mut self, shape: &Shape) {
+        match shape {
+            Shape::Rect(rect) => {
+                let radius = rect
+                    .rounding
+                    .nw
+                    .min(rect.rect.width() / 2.0)
+                    .min(rect.rect.height() / 2.0)
+                    .max(0.0);
+                let inner = rect.rect.shrink(radius);
+                self.fill(rect.rect, rect.fill, |p| inner.distance_to_pos(p) <= radius);
+            }
+            Shape::LineSegment { points, stroke } => {
+                let [a, b] = *points;
+                let half = stroke.width / 2.0;
+                let bounds = Rect::from_two_pos(a, b).expand(half);
+                self.fill(bounds, stroke.color, |p| segment_distance(p, a, b) <= half);
+            }
+            Shape::Circle(circle) => {
+                let bounds = Rect::from_center_size(circle.center, [circle.radius * 2.0; 2].into());
+                self.fill(bounds, circle.fill, |p| {
+                    p.distance(circle.center) <= circle.radius
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Blends `color` over every pixel in `bounds` whose center `covers` accepts.
+    fn fill(&mut self, bounds: Rect, color: Color32, covers: impl Fn(Pos2) -> bool) {
+        let columns = bounds.min.x.floor().max(0.0) as u32
+            ..(bounds.max.x.ceil().max(0.0) as u32).min(self.width);
+        let rows = bounds.min.y.floor().max(0.0) as u32
+            ..(bounds.max.y.ceil().max(0.0) as u32).min(self.height);
+        for y in rows {
+            for x in columns.clone() {
+                let center = pos2(x as f32 + 0.5, y as f32 + 0.5);
+                if bounds.contains(center) && covers(center) {
+                    self.blend(x, y, color);
+                }
+            }
+        }
+    }
+
+    /// Blends the premultiplied `color` over the pixel at `x`, `y`.
+    fn blend(&mut self, x: u32, y: u32, color: Color32) {
+        let offset = (y as usize * self.width as usize + x as usize) * 4;
+        let keep = 255 - u32::from(color.a());
+        for (channel, source) in self.pixels[offset..offset + 4]
+            .iter_mut()
+            .zip(color.to_array())
+        {
+            *channel = (u32::from(source) + u32::from(*channel) * keep / 255).min(255) as u8;
+        }
+    }
+}
+
+/// Returns the distance from `p` to the segment between `a` and `b`.
+fn segment_distance(p: Pos2, a: Pos2, b: Pos2) -> f32 {
+    let along = b - a;
+    let length_sq = along.length_sq();
+    if length_sq == 0.0 {
+        return p.distance(a);
+    }
+    let t = ((p - a).dot(along) / length_sq).clamp(0.0, 1.0);
+    p.distance(a + along * t)
+}
+
+/// Runs `sorter` headless on a copy of `numbers` and renders the bar view of every
+/// `options.every`-th step, as well as the first and the last.
+/// # Arguments
+/// * `emit` - Receives every frame, and whether it is the last one.
+/// # Returns
+/// The number of frames rendered, or the first error `emit` returned.
+pub(crate) fn render_run(
+    numbers: &[usize],
+    sorter: &mut dyn Sorter,
+    theme: Theme,
+    options: &ExportOptions,
+    mut emit: impl FnMut(&Canvas, bool) -> io::Result<()>,
+) -> io::Result<usize> {
+    let (width, height) = (u32::from(options.width), u32::from(options.height));
+    let layout = Layout::image(width, height);
+    let render = |array: &[usize], sorter: &dyn Sorter, finished: bool| {
+        let mut canvas = Canvas::new(width, height, theme.background_color());
+        let view = SorterView::of(sorter, array.len());
+        for shape in &scene::bars(array, &view, finished, theme, &layout).shapes {
+            canvas.draw(shape);
+        }
+        canvas
+    };
+
+    let mut array = numbers.to_vec();
+    emit(&render(&array, sorter, false), false)?;
+    let mut frames = 1;
+    sound::muted(|| {
+        for step in 1..=MAX_EXPORT_STEPS {
+            let finished = sorter.step(&mut array);
+            let last = finished || step == MAX_EXPORT_STEPS;
+            if last || step % options.every.max(1) == 0 {
+                emit(&render(&array, sorter, finished), last)?;
+                frames += 1;
+            }
+            if last {
+                break;
+            }
+        }
+        Ok(frames)
+    })
+}
+
+/// Runs `sorter` headless and writes the frames of `render_run` to `writer` as a looping GIF.
+/// # Returns
+/// The number of frames written.
+pub(crate) fn write_gif(
+    writer: impl Write,
+    numbers: &[usize],
+    sorter: &mut dyn Sorter,
+    theme: Theme,
+    options: &ExportOptions,
+) -> io::Result<usize> {
+    let (width, height) = (options.width, options.height);
+    let mut encoder = gif::Encoder::new(writer, width, height, &[]).map_err(io::Error::other)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(io::Error::other)?;
+    render_run(numbers, sorter, theme, options, |canvas, last| {
+        let mut pixels = canvas.pixels.clone();
+        let mut frame = gif::Frame::from_rgba_speed(width, height, &mut pixels, 10);
+        frame.delay = if last { FINAL_DELAY } else { FRAME_DELAY };
+        encoder.write_frame(&frame).map_err(io::Error::other)
+    })
+}
+
+/// Runs `sorter` headless and saves the frames of `render_run` in `folder`
+/// as `frame_00000.png`, `frame_00001.png` and so on.
+/// # Returns
+/// The number of frames written.
+pub(crate) fn write_png_frames(
+    folder: &Path,
+    numbers: &[usize],
+    sorter: &mut dyn Sorter,
+    theme: Theme,
+    options: &ExportOptions,
+) -> io::Result<usize> {
+    fs::create_dir_all(folder)?;
+    let mut index = 0;
+    render_run(numbers, sorter, theme, options, |canvas, _| {
+        let path = folder.join(format!("frame_{:05}.png", index));
+        index += 1;
+        write_png(BufWriter::new(File::create(path)?), canvas)
+    })
+}
+
+/// Encodes `canvas` as a PNG image into `writer`.
+pub(crate) fn write_png(writer: impl Write, canvas: &Canvas) -> io::Result<()> {
+    let mut encoder = png::Encoder::new(writer, canvas.width, canvas.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&canvas.pixels)?;
+    Ok(())
+}
+
+/// Height of the legend above the bars of an SVG, in pixels.
+const LEGEND_HEIGHT: f32 = 30.0;
+
+/// Writes `scene` as an SVG image of `width` x `height` pixels for the bars,
+/// with a legend of the colors it uses above them.
+pub(crate) fn to_svg(scene: &Scene, width: u32, height: u32, theme: Theme) -> String {
+    let mut svg = String::new();
+    let total_height = height as f32 + LEGEND_HEIGHT;
+    // Writing to a `String` cannot fail.
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{total_height}" viewBox="0 0 {width} {total_height}" font-family="sans-serif" font-size="12">"#
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="100%" height="100%" {}/>"#,
+        svg_paint("fill", theme.background_color())
+    );
+
+    // The legend lists the plain bar color and every highlight color the scene uses.
+    let entries = std::iter::once(("Element", theme.bar_color())).chain(scene.legend.clone());
+    let mut x = 10.0;
+    for (name, color) in entries {
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{x}" y="9" width="12" height="12" rx="2" {}/><text x="{}" y="15" dominant-baseline="middle" {}>{name}</text>"#,
+            svg_paint("fill", color),
+            x + 16.0,
+            svg_paint("fill", theme.text_color())
+        );
+        x += 28.0 + 7.0 * name.len() as f32;
+    }
+
+    let _ = writeln!(svg, r#"<g transform="translate(0 {LEGEND_HEIGHT})">"#);
+    for shape in &scene.shapes {
+        match shape {
+            Shape::Rect(rect) => {
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" rx="{}" {}/>"#,
+                    rect.rect.min.x,
+                    rect.rect.min.y,
+                    rect.rect.width(),
+                    rect.rect.height(),
+                    rect.rounding.nw,
+                    svg_paint("fill", rect.fill)
+                );
+            }
+            Shape::LineSegment { points, stroke } => {
+                let _ = writeln!(
+                    svg,
+                    r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke-width="{}" {}/>"#,
+                    points[0].x,
+                    points[0].y,
+                    points[1].x,
+                    points[1].y,
+                    stroke.width,
+                    svg_paint("stroke", stroke.color)
+                );
+            }
+            Shape::Circle(circle) => {
+                let _ = writeln!(
+                    svg,
+                    r#"<circle cx="{:.2}" cy="{:.2}" r="{}" {}/>"#,
+                    circle.center.x,
+                    circle.center.y,
+                    circle.radius,
+                    svg_paint("fill", circle.fill)
+                );
+            }
+            _ => {}
+        }
+    }
+    for label in &scene.labels {
+        let anchor = match label.align.x() {
+            Align::Min => "start",
+            Align::Center => "middle",
+            Align::Max => "end",
+        };
+        let baseline = match label.align.y() {
+            Align::Min => "hanging",
+            Align::Center => "middle",
+            Align::Max => "auto",
+        };
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.2}" y="{:.2}" text-anchor="{anchor}" dominant-baseline="{baseline}" {}>{}</text>"#,
+            label.pos.x,
+            label.pos.y,
+            svg_paint("fill", label.color),
+            escape_xml(&label.text)
+        );
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+/// Returns the SVG attributes painting `attribute` (`fill` or `stroke`) with the
+/// premultiplied `color`, with an opacity if it is translucent.
+fn svg_paint(attribute: &str, color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    if a == 255 {
+        format!(r##"{attribute}="#{r:02x}{g:02x}{b:02x}""##)
+    } else {
+        format!(
+            r##"{attribute}="#{r:02x}{g:02x}{b:02x}" {attribute}-opacity="{:.3}""##,
+            a as f32 / 255.0
+        )
+    }
+}
+
+/// Escapes the characters of `text` that are markup in XML.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::bubble_sort::BubbleSort;
+
+    /// Returns the pixel of `canvas` at `x`, `y`.
+    fn pixel(canvas: &Canvas, x: u32, y: u32) -> [u8; 4] {
+        let offset = (y * canvas.width + x) as usize * 4;
+        canvas.pixels[offset..offset + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn frames_show_the_bars_of_every_nth_step() {
+        let options = ExportOptions {
+            every: 2,
+            width: 60,
+            height: 50,
+            ..Default::default()
+        };
+        let mut frames = Vec::new();
+        let count = render_run(
+            &[3, 1, 2],
+            &mut BubbleSort::new(),
+            Theme::Dark,
+            &options,
+            |canvas, last| {
+                frames.push((pixel(canvas, 2, 10), pixel(canvas, 50, 48), last));
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(count, frames.len());
+        assert!(frames.len() > 2, "only {} frames", frames.len());
+        assert!(frames[..frames.len() - 1].iter().all(|&(.., last)| !last));
+        let (margin, last_bar, last) = frames[frames.len() - 1];
+        assert!(last);
+        assert_eq!(margin, Theme::Dark.background_color().to_array());
+        assert_eq!(last_bar, Theme::Dark.bar_color().to_array());
+    }
+
+    #[test]
+    fn gif_holds_one_image_per_frame() {
+        let options = ExportOptions {
+            width: 40,
+            height: 30,
+            ..Default::default()
+        };
+        let mut gif = Vec::new();
+        let count = write_gif(
+            &mut gif,
+            &[4, 2, 3, 1],
+            &mut BubbleSort::new(),
+            Theme::Light,
+            &options,
+        )
+        .unwrap();
+
+        let mut decoder = gif::DecodeOptions::new().read_info(&gif[..]).unwrap();
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!((frame.width, frame.height), (40, 30));
+            delays.push(frame.delay);
+        }
+        assert_eq!(delays.len(), count);
+        assert_eq!(delays.last(), Some(&FINAL_DELAY));
+    }
+
+    #[test]
+    fn svg_has_a_bar_per_element_and_a_legend_of_used_colors() {
+        let mut sorter = BubbleSort::new();
+        let mut numbers = vec![3, 1, 2];
+        sound::muted(|| sorter.step(&mut numbers));
+        let layout = Layout::image(300, 200);
+        let view = SorterView::of(&sorter, numbers.len());
+        let scene = scene::bars(&numbers, &view, false, Theme::Winter, &layout);
+        let svg = to_svg(&scene, 300, 200, Theme::Winter);
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        let bars = svg.matches(r#"rx="4""#).count();
+        assert_eq!(bars, numbers.len());
+        let highlight = svg_paint("fill", scene.legend[0].1);
+        assert!(
+            svg.matches(&highlight).count() >= 2,
+            "no highlighted bar in\n{svg}"
+        );
+        assert!(svg.contains(">Element</text>"));
+        assert!(!svg.contains(">Pivot</text>"));
+        assert_eq!(escape_xml("a<b & c"), "a&lt;b &amp; c");
+    }
+}
@@ -0,0 +1,114 @@
+use crate::benchmark::BenchmarkResult;
+use crate::shuffle::ShuffleStats;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+/// What a finished job hands back to the window.
+pub(crate) enum Outcome {
+    Benchmark(Vec<BenchmarkResult>),
+    ShuffleStats(ShuffleStats),
+    Saved, // A file was written, or the job reported why it could not be.
+}
+
+/// How far a job has come, shared between its thread and the window.
+#[derive(Clone, Default)]
+pub(crate) struct Progress {
+    done: Arc<AtomicUsize>,
+    total: Arc<AtomicUsize>, // 0 while the amount of work is unknown.
+}
+
+impl Progress {
+    /// Declares how many parts the job consists of.
+    pub fn set_total(&self, total: usize) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    /// Marks one more part as done.
+    pub fn advance(&self) {
+        self.done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the part of the job done so far, or `None` if its size is unknown.
+    pub fn fraction(&self) -> Option<f32> {
+        let total = self.total.load(Ordering::Relaxed);
+        (total > 0).then(|| self.done.load(Ordering::Relaxed) as f32 / total as f32)
+    }
+}
+
+/// A headless run started from the window, such as a benchmark or an export.
+/// It works on a background thread, so the window keeps drawing while it runs.
+pub(crate) struct Job {
+    pub name: &'static str, // Shown next to the progress while the job runs.
+    pub progress: Progress,
+    pub started: Instant,
+    outcome: Receiver<Outcome>,
+}
+
+impl Job {
+    /// Starts `work` on a new thread, handing it a `Progress` to report to.
+    pub fn start(
+        name: &'static str,
+        work: impl FnOnce(&Progress) -> Outcome + Send + 'static,
+    ) -> Self {
+        let progress = Progress::default();
+        let (posted, outcome) = mpsc::channel();
+        let reported = progress.clone();
+        thread::spawn(move || {
+            // The window may have been closed in the meantime.
+            let _ = posted.send(work(&reported));
+        });
+        Self {
+            name,
+            progress,
+            started: Instant::now(),
+            outcome,
+        }
+    }
+
+    /// Returns the outcome once the job is done, without waiting.
+    /// # Returns
+    /// `None` while the job runs, `Some(None)` if it panicked.
+    pub fn outcome(&self) -> Option<Option<Outcome>> {
+        match self.outcome.try_recv() {
+            Ok(outcome) => Some(Some(outcome)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Waits for `job` to finish and returns its outcome.
+    fn wait(job: &Job) -> Option<Outcome> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            if let Some(outcome) = job.outcome() {
+                return outcome;
+            }
+            assert!(Instant::now() < deadline, "the job did not finish");
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn outcomes_arrive_once_the_work_is_done() {
+        let job = Job::start("Counting", |progress| {
+            progress.set_total(2);
+            progress.advance();
+            Outcome::Saved
+        });
+        assert!(matches!(wait(&job), Some(Outcome::Saved)));
+        assert_eq!(job.progress.fraction(), Some(0.5));
+
+        let job = Job::start("Failing", |_| panic!("the job failed"));
+        assert!(wait(&job).is_none());
+        assert_eq!(job.progress.fraction(), None);
+    }
+}
@@ -1,42 +1,61 @@
 mod buttons;
 pub mod constants;
+mod export;
+mod jobs;
+mod panels;
+mod scene;
+mod session;
+mod spacetime;
 use rfd::FileDialog;
 use std::fs;
 use strum::IntoEnumIterator;
 
 use self::constants::{Theme, CEIL, FLOOR, VECTOR_SIZE};
-use crate::algorithms::{
-    bogo_sort::BogoSort, bubble_sort::BubbleSort, cocktail_sort::CocktailSort, comb_sort::CombSort,
-    counting_sort::CountingSort, gnome_sort::GnomeSort, heap_sort::HeapSort,
-    insertion_sort::InsertionSort, merge_sort::MergeSort, odd_even_sort::OddEvenSort,
-    pancake_sort::PancakeSort, quick_sort::QuickSort, selection_sort::SelectionSort,
-    shell_sort::ShellSort, Reasons, Sorter,
-};
+use self::export::{ExportFormat, ExportOptions};
+use self::jobs::{Job, Outcome, Progress};
+use self::scene::Layout;
+use self::session::{Session, SorterView};
+use self::spacetime::Spacetime;
+use crate::algorithms::{bubble_sort::BubbleSort, input_limit, Sorter};
+use crate::api::{Api, Command, Request, Status};
+use crate::benchmark::{self, BenchmarkResult};
+use crate::params::{self, ParamKind, ParamValue, Settings};
 use crate::random::gen_random_vector;
-use crate::types::{Algorithms, State, STEP_DELAY};
+use crate::shuffle::{ShuffleMethod, ShuffleStats};
+use crate::trace::{Trace, TracePlayer};
+use crate::types::{Algorithms, Distribution, State};
 use buttons::ButtonHandler;
-use eframe::{
-    egui::{self, Button, ComboBox, Ui},
-    epaint::{vec2, Color32},
-};
-use std::{thread, time::Instant};
+use eframe::egui::{self, Button, ComboBox, Ui};
+use std::{mem, time::Instant};
 
 /// Main structure managing the visualizer's state, data, and behavior.
-pub(crate) struct Visualizer<'a> {
+pub(crate) struct Visualizer {
     selected_algorithm: Algorithms, // The currently selected sorting algorithm.
     numbers: Vec<usize>,            // The array of numbers being sorted.
     original_numbers: Vec<usize>,   // A copy of the original unsorted array.
-    state: State, // The current state of the visualizer (Start, Running, Finished).
-    sorter: Box<dyn Sorter + 'a>, // The sorting algorithm instance.
+    state: State,     // The current state of the visualizer (Start, Running, Finished).
+    session: Session, // The sorting algorithm instance, stepped on a worker thread.
+    view: SorterView, // The sorter as of the last update of the session.
     start_time: Option<Instant>, // Timer tracking the start of sorting.
     total_elapsed_time: f64, // Total elapsed time of the sorting process.
     selected_theme: Theme, // The currently selected theme.
     user_input: String,
-    comparisons: usize, //  Initialize comparisons
-    swaps: usize,       //  Initialize swaps
+    comparisons: usize,                      //  Initialize comparisons
+    swaps: usize,                            //  Initialize swaps
+    selected_distribution: Distribution,     // Shape of the data generated by "Shuffle".
+    settings: Settings,                      // Tunable parameters of every algorithm.
+    benchmark: Option<Vec<BenchmarkResult>>, // Results of the last benchmark run, if shown.
+    selected_shuffle: Option<ShuffleMethod>, // Animated shuffle for "Shuffle", or `None` for new data.
+    shuffling: bool,                         // Whether `sorter` is currently an animated shuffle.
+    shuffle_stats: Option<ShuffleStats>,     // Shuffle statistics, if their window is open.
+    animation_export: Option<ExportOptions>, // Animation export options, if their window is open.
+    spacetime: Option<Spacetime>,            // Space-time diagram of the run, if its panel is open.
+    api: Option<Api>,                        // Control API server, if started with `--api`.
+    replies: Vec<(u64, Request)>, // API requests waiting for the session to handle their commands.
+    job: Option<Job>,             // Headless run working in the background, if any.
 }
 
-impl<'a> Default for Visualizer<'a> {
+impl Default for Visualizer {
     /// Creates a default instance of the visualizer with the Bubble Sort algorithm and dark theme.
     fn default() -> Self {
         let numbers = gen_random_vector(FLOOR, CEIL, VECTOR_SIZE);
@@ -45,67 +64,74 @@ impl<'a> Default for Visualizer<'a> {
             .map(|n| n.to_string()) // Convert numbers to strings
             .collect::<Vec<_>>() // Collect into a vector
             .join(","); // Join into a single comma-separated string
+        let sorter = BubbleSort::new();
+        let view = SorterView::of(&sorter, numbers.len());
+        let session = Session::start(Box::new(sorter), numbers.clone(), false);
         Self {
             selected_algorithm: Algorithms::Bubble,
             numbers: numbers.clone(),
             original_numbers: numbers,
             state: State::Start,
-            sorter: Box::new(BubbleSort::new()),
+            session,
+            view,
             start_time: None,
             total_elapsed_time: 0.0,
             selected_theme: Theme::Dark, // Default theme is dark
             user_input: numbers_string,
             comparisons: 0,
             swaps: 0,
+            selected_distribution: Distribution::Random,
+            settings: Settings::default(),
+            benchmark: None,
+            selected_shuffle: None,
+            shuffling: false,
+            shuffle_stats: None,
+            animation_export: None,
+            spacetime: None,
+            api: None,
+            replies: Vec::new(),
+            job: None,
         }
     }
 }
 
-impl Visualizer<'_> {
-    /// Creates a new instance of the visualizer.
-    pub(crate) fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Self::default()
+impl Visualizer {
+    /// Creates a new instance of the visualizer, replaying `trace` if one was given
+    /// and answering the requests of `api` if it was started.
+    pub(crate) fn new(
+        _cc: &eframe::CreationContext<'_>,
+        trace: Option<Trace>,
+        api: Option<Api>,
+    ) -> Self {
+        let mut visualizer = Self {
+            api,
+            ..Self::default()
+        };
+        if let Some(trace) = trace {
+            visualizer.replay(trace);
+        }
+        visualizer
     }
 
     /// Draws the bars representing the current state of the array.
     fn draw_bars(&self, ui: &mut Ui) {
-        let window_width = ui.available_width();
-        let window_height = ui.available_height();
-        let num_bars = self.numbers.len().max(1); // Prevent division by zero
-        let spacing = 5.0; // Reduce space between bars for better fit
-        let total_spacing = spacing * (num_bars - 1) as f32;
-        let bar_width = ((window_width - total_spacing) / num_bars as f32).max(2.0); // Ensure minimum width
-
-        let max_value = *self.numbers.iter().max().unwrap_or(&1); // Get max value to scale height
-
-        let top_ui_height = 150.0;
-        let graph_height = (window_height - top_ui_height).max(250.0); // Maximize graph usage
-
+        let scene = scene::bars(
+            &self.numbers,
+            &self.view,
+            self.state == State::Finished,
+            self.selected_theme,
+            &Layout::window(ui.available_size()),
+        );
         let painter = ui.painter();
-
-        for (index, &value) in self.numbers.iter().enumerate() {
-            let x = index as f32 * (bar_width + spacing) + 5.0;
-            let bar_height = ((value as f32 / max_value as f32) * graph_height).max(10.0);
-            let y = window_height - bar_height + 96.0; // bars align properly at bottom
-
-            let color = self.get_bar_color(index);
-            let rect = egui::Rect::from_min_size(egui::pos2(x, y), vec2(bar_width, bar_height));
-
-            painter.rect_filled(rect, 4.0, color);
-        }
-    }
-
-    /// Determines the color of a bar based on the sorting state and indices.
-    fn get_bar_color(&self, index: usize) -> Color32 {
-        if self.state != State::Finished
-            && (index == self.sorter.special().0 || index == self.sorter.special().1)
-        {
-            match self.sorter.reason() {
-                Reasons::Comparing => Color32::LIGHT_YELLOW,
-                Reasons::Switching => Color32::LIGHT_GREEN,
-            }
-        } else {
-            self.selected_theme.bar_color() // Bar color based on the selected theme
+        painter.extend(scene.shapes);
+        for label in scene.labels {
+            painter.text(
+                label.pos,
+                label.align,
+                label.text,
+                egui::FontId::proportional(12.0),
+                label.color,
+            );
         }
     }
 
@@ -114,14 +140,10 @@ impl Visualizer<'_> {
         let previous = self.selected_algorithm;
         ui.label("Algorithm:");
         ComboBox::from_id_source("algorithm_selector")
-            .selected_text(format!("{:?} Sort", self.selected_algorithm))
+            .selected_text(self.selected_algorithm.label())
             .show_ui(ui, |ui| {
                 Algorithms::iter().for_each(|alg| {
-                    ui.selectable_value(
-                        &mut self.selected_algorithm,
-                        alg,
-                        format!("{:?} Sort", alg),
-                    );
+                    ui.selectable_value(&mut self.selected_algorithm, alg, alg.label());
                 });
             });
         if previous != self.selected_algorithm {
@@ -151,38 +173,535 @@ impl Visualizer<'_> {
             });
     }
 
+    /// Handles the selection of the input distribution used when shuffling.
+    fn handle_distribution_selection(&mut self, ui: &mut Ui) {
+        ui.label("Input:");
+        ComboBox::from_id_source("distribution_selector")
+            .selected_text(format!("{:?}", self.selected_distribution))
+            .show_ui(ui, |ui| {
+                Distribution::iter().for_each(|distribution| {
+                    ui.selectable_value(
+                        &mut self.selected_distribution,
+                        distribution,
+                        format!("{:?}", distribution),
+                    );
+                });
+            });
+    }
+
+    /// Handles the selection of what the "Shuffle" button does:
+    /// generate new data, or animate a shuffle of the current numbers.
+    fn handle_shuffle_selection(&mut self, ui: &mut Ui) {
+        let label = |method: Option<ShuffleMethod>| method.map_or("New data", ShuffleMethod::label);
+        ui.label("Shuffle:");
+        ComboBox::from_id_source("shuffle_selector")
+            .selected_text(label(self.selected_shuffle))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.selected_shuffle, None, label(None));
+                ShuffleMethod::iter().for_each(|method| {
+                    ui.selectable_value(&mut self.selected_shuffle, Some(method), method.label());
+                });
+            });
+    }
+
+    /// Draws a control for every tunable parameter of the selected algorithm,
+    /// generated from its declared parameters, and rebuilds the sorter on change.
+    fn handle_parameters(&mut self, ui: &mut Ui) {
+        let algorithm = self.selected_algorithm;
+        let mut values = self.settings.values(algorithm);
+        let previous = values.clone();
+
+        for (parameter, value) in params::parameters(algorithm).iter().zip(values.iter_mut()) {
+            ui.label(format!("{}:", parameter.name));
+            match (&parameter.kind, value) {
+                (ParamKind::Integer { min, max }, ParamValue::Integer(value)) => {
+                    ui.add(egui::Slider::new(value, *min..=*max));
+                }
+                (ParamKind::Decimal { min, max }, ParamValue::Decimal(value)) => {
+                    ui.add(egui::Slider::new(value, *min..=*max).fixed_decimals(2));
+                }
+                (ParamKind::Choice { options, .. }, ParamValue::Choice(index)) => {
+                    ComboBox::from_id_source(parameter.name)
+                        .selected_text(options.get(*index).cloned().unwrap_or_default())
+                        .show_ui(ui, |ui| {
+                            for (option_index, option) in options.iter().enumerate() {
+                                ui.selectable_value(index, option_index, option);
+                            }
+                        });
+                }
+                _ => {}
+            }
+        }
+
+        if values != previous {
+            self.settings.set_values(algorithm, values);
+            self.switch_algorithm();
+        }
+    }
+
     /// Switches the current sorting algorithm and resets the visualizer.
     fn switch_algorithm(&mut self) {
-        self.sorter = match self.selected_algorithm {
-            Algorithms::Bubble => Box::new(BubbleSort::new()),
-            Algorithms::Selection => Box::new(SelectionSort::new()),
-            Algorithms::Insertion => Box::new(InsertionSort::new()),
-            Algorithms::Merge => Box::new(MergeSort::new()),
-            Algorithms::Bogo => Box::new(BogoSort::new()),
-            Algorithms::Heap => Box::new(HeapSort::new()),
-            Algorithms::Quick => Box::new(QuickSort::new()),
-            Algorithms::Counting => Box::new(CountingSort::new()),
-            Algorithms::Cocktail => Box::new(CocktailSort::new()),
-            Algorithms::Gnome => Box::new(GnomeSort::new()),
-            Algorithms::Pancake => Box::new(PancakeSort::new()),
-            Algorithms::Shell => Box::new(ShellSort::new()),
-            Algorithms::Comb => Box::new(CombSort::new()),
-            Algorithms::OddEven => Box::new(OddEvenSort::new()),
+        self.shuffling = false;
+        self.clear_spacetime();
+        self.session
+            .load(self.settings.create_sorter(self.selected_algorithm));
+        self.reset();
+    }
+
+    /// Keeps the shuffled numbers as the new input and switches back to the selected algorithm.
+    fn finish_shuffle(&mut self) {
+        self.set_input(self.numbers.clone());
+        self.user_input = self
+            .numbers
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        self.switch_algorithm();
+    }
+
+    /// Shows the position/value heatmap of many shuffles in a separate window.
+    fn show_shuffle_stats(&mut self, ctx: &egui::Context) {
+        let Some(stats) = &self.shuffle_stats else {
+            return;
         };
+        let (mut method, mut size, mut trials) = (stats.method, stats.size, stats.trials);
+        let mut open = true;
+        let mut rerun = false;
+        egui::Window::new("🎲 Shuffle statistics")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ComboBox::from_id_source("stats_shuffle_selector")
+                        .selected_text(method.label())
+                        .show_ui(ui, |ui| {
+                            ShuffleMethod::iter().for_each(|option| {
+                                ui.selectable_value(&mut method, option, option.label());
+                            });
+                        });
+                    ui.label("Elements:");
+                    ui.add(egui::Slider::new(&mut size, 3..=16));
+                    ui.label("Shuffles:");
+                    ui.add(egui::Slider::new(&mut trials, 1_000..=200_000).logarithmic(true));
+                    rerun = ui
+                        .add_enabled(self.job.is_none(), Button::new("▶ Run"))
+                        .clicked();
+                });
+                panels::draw_heatmap(ui, stats, self.selected_theme);
+            });
+        if let Some(stats) = &mut self.shuffle_stats {
+            (stats.method, stats.size, stats.trials) = (method, size, trials);
+        }
+        if rerun {
+            self.run_shuffle_stats(method, size, trials);
+        }
+        if !open {
+            self.shuffle_stats = None;
+        }
+    }
+
+    /// Runs every algorithm, and ShellSort with every gap sequence, headless on the
+    /// original numbers in the background. The results are shown once all are done.
+    fn run_benchmark(&mut self) {
+        let numbers = self.original_numbers.clone();
+        let settings = self.settings.clone();
+        self.start_job("Benchmark", move |progress| {
+            progress.set_total(benchmark::run_count());
+            let mut results = benchmark::run_all(
+                &numbers,
+                |algorithm| settings.create_sorter(algorithm),
+                |algorithm| settings.describe(algorithm),
+                || progress.advance(),
+            );
+            results.extend(benchmark::run_gap_sequences(&numbers, || {
+                progress.advance()
+            }));
+            Outcome::Benchmark(results)
+        });
+    }
+
+    /// Shuffles `size` elements `trials` times with `method` in the background
+    /// and shows the statistics once they are collected.
+    fn run_shuffle_stats(&mut self, method: ShuffleMethod, size: usize, trials: usize) {
+        self.start_job("Shuffle statistics", move |_| {
+            Outcome::ShuffleStats(ShuffleStats::run(method, size, trials))
+        });
+    }
+
+    /// Starts `work` as the background job, unless one is already running.
+    fn start_job(
+        &mut self,
+        name: &'static str,
+        work: impl FnOnce(&Progress) -> Outcome + Send + 'static,
+    ) {
+        if self.job.is_none() {
+            self.job = Some(Job::start(name, work));
+        }
+    }
+
+    /// Takes the outcome of the background job once it is done.
+    fn handle_job(&mut self) {
+        let Some(outcome) = self.job.as_ref().and_then(Job::outcome) else {
+            return;
+        };
+        let job = self.job.take().expect("the job just finished");
+        match outcome {
+            Some(Outcome::Benchmark(results)) => self.benchmark = Some(results),
+            Some(Outcome::ShuffleStats(stats)) => self.shuffle_stats = Some(stats),
+            Some(Outcome::Saved) => {}
+            None => eprintln!("{} failed", job.name),
+        }
+    }
+
+    /// Shows the background job with its progress, or a spinner if its size is unknown.
+    fn draw_job(&self, ui: &mut Ui) {
+        let Some(job) = &self.job else {
+            return;
+        };
+        match job.progress.fraction() {
+            Some(fraction) => {
+                ui.add(
+                    egui::ProgressBar::new(fraction)
+                        .desired_width(140.0)
+                        .text(format!("{} {:.0}%", job.name, fraction * 100.0)),
+                );
+            }
+            None => {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{}… {:.1}s",
+                        job.name,
+                        job.started.elapsed().as_secs_f32()
+                    ))
+                    .color(self.selected_theme.text_color()),
+                );
+                ui.add(egui::Spinner::new());
+            }
+        }
+    }
+
+    /// Shows the results of the last benchmark run in a separate window.
+    fn show_benchmark(&mut self, ctx: &egui::Context) {
+        let Some(results) = &self.benchmark else {
+            return;
+        };
+        let mut open = true;
+        egui::Window::new("📈 Benchmark")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "Every algorithm run on the same {} numbers.",
+                        self.original_numbers.len()
+                    ));
+                    if ui.button("💾 Export CSV").clicked() {
+                        Self::export_benchmark(results);
+                    }
+                });
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("benchmark_grid")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for header in [
+                                "Algorithm",
+                                "Steps",
+                                "Comparisons",
+                                "Swaps",
+                                "Writes",
+                                "Peak extra memory",
+                                "Parameters",
+                            ] {
+                                ui.strong(header);
+                            }
+                            ui.end_row();
+                            for result in results {
+                                ui.label(&result.name);
+                                if result.finished {
+                                    ui.label(result.steps.to_string());
+                                } else {
+                                    ui.label(format!("> {} (stopped)", result.steps));
+                                }
+                                ui.label(result.comparisons.to_string());
+                                ui.label(result.swaps.to_string());
+                                ui.label(result.writes.to_string());
+                                ui.label(result.peak_memory.to_string());
+                                ui.label(&result.parameters);
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        if !open {
+            self.benchmark = None;
+        }
+    }
+
+    /// Shows the options of an animation export in a separate window.
+    fn show_animation_export(&mut self, ctx: &egui::Context) {
+        let Some(options) = &mut self.animation_export else {
+            return;
+        };
+        let mut open = true;
+        let mut export = false;
+        egui::Window::new("🎞 Export animation")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Renders {} on the original {} numbers with the {:?} theme.",
+                    self.selected_algorithm.label(),
+                    self.original_numbers.len(),
+                    self.selected_theme
+                ));
+                egui::Grid::new("export_grid").show(ui, |ui| {
+                    ui.label("Format:");
+                    ComboBox::from_id_source("export_format_selector")
+                        .selected_text(options.format.label())
+                        .show_ui(ui, |ui| {
+                            for format in [ExportFormat::Gif, ExportFormat::PngFrames] {
+                                ui.selectable_value(&mut options.format, format, format.label());
+                            }
+                        });
+                    ui.end_row();
+                    ui.label("Every N steps:");
+                    ui.add(egui::Slider::new(&mut options.every, 1..=1_000).logarithmic(true));
+                    ui.end_row();
+                    ui.label("Width:");
+                    ui.add(egui::Slider::new(&mut options.width, 100..=1920));
+                    ui.end_row();
+                    ui.label("Height:");
+                    ui.add(egui::Slider::new(&mut options.height, 100..=1080));
+                    ui.end_row();
+                });
+                export = ui
+                    .add_enabled(self.job.is_none(), Button::new("💾 Export"))
+                    .clicked();
+            });
+        if export {
+            let options = *options;
+            self.export_animation(&options);
+        }
+        if !open {
+            self.animation_export = None;
+        }
+    }
+
+    /// Asks where to save the animation, then runs the selected algorithm headless
+    /// from the original numbers in the background and writes every rendered frame there.
+    fn export_animation(&mut self, options: &ExportOptions) {
+        let path = match options.format {
+            ExportFormat::Gif => FileDialog::new()
+                .add_filter("GIF Images", &["gif"])
+                .set_file_name("sort.gif")
+                .save_file(),
+            ExportFormat::PngFrames => FileDialog::new().pick_folder(),
+        };
+        let Some(path) = path else {
+            return;
+        };
+        let mut sorter = self.settings.create_sorter(self.selected_algorithm);
+        let limit = input_limit(sorter.as_ref(), self.original_numbers.len());
+        let numbers = self.original_numbers[..limit].to_vec();
+        let (theme, options) = (self.selected_theme, *options);
+        self.start_job("Exporting animation", move |_| {
+            let result = match options.format {
+                ExportFormat::Gif => fs::File::create(&path).and_then(|file| {
+                    export::write_gif(file, &numbers, sorter.as_mut(), theme, &options)
+                }),
+                ExportFormat::PngFrames => {
+                    export::write_png_frames(&path, &numbers, sorter.as_mut(), theme, &options)
+                }
+            };
+            if let Err(error) = result {
+                eprintln!("Failed to export animation: {}", error);
+            }
+            Outcome::Saved
+        });
+    }
+
+    /// Asks for a file name and saves the current bars, highlights and labels there as SVG,
+    /// laid out like the window at a fixed size so printed handouts match each other.
+    fn export_svg(&self) {
+        let Some(path) = FileDialog::new()
+            .add_filter("SVG Images", &["svg"])
+            .set_file_name("frame.svg")
+            .save_file()
+        else {
+            return;
+        };
+        let (width, height) = (1000, 500);
+        let scene = scene::bars(
+            &self.numbers,
+            &self.view,
+            self.state == State::Finished,
+            self.selected_theme,
+            &Layout::image(width, height),
+        );
+        let svg = export::to_svg(&scene, width, height, self.selected_theme);
+        if let Err(error) = fs::write(&path, svg) {
+            eprintln!("Failed to export SVG to {}: {}", path.display(), error);
+        }
+    }
+
+    /// Asks for a file name, records a run of the selected algorithm on the original numbers
+    /// with a fresh seed in the background, and saves it there as a trace file.
+    fn save_trace(&mut self) {
+        let Some(path) = FileDialog::new()
+            .add_filter("Trace Files", &["json"])
+            .set_file_name("trace.json")
+            .save_file()
+        else {
+            return;
+        };
+        let (algorithm, settings) = (self.selected_algorithm, self.settings.clone());
+        let numbers = self.original_numbers.clone();
+        self.start_job("Recording trace", move |_| {
+            let trace = Trace::record(algorithm, &settings, rand::random(), &numbers);
+            if let Err(error) = fs::write(&path, trace.to_json()) {
+                eprintln!("Failed to save trace to {}: {}", path.display(), error);
+            }
+            Outcome::Saved
+        });
+    }
+
+    /// Opens a trace file, saved by the visualizer or written by an external program,
+    /// and replays it.
+    fn open_trace(&mut self) {
+        let Some(path) = FileDialog::new()
+            .add_filter("Trace Files", &["json", "ndjson", "jsonl"])
+            .pick_file()
+        else {
+            return;
+        };
+        match Trace::load(&path) {
+            Ok(trace) => self.replay(trace),
+            Err(error) => eprintln!("Failed to open trace {}: {}", path.display(), error),
+        }
+    }
+
+    /// Replays `trace` from its input in place of the selected algorithm, which is switched
+    /// to the recorded one when it is known. Its steps come from the trace, not the algorithm.
+    fn replay(&mut self, trace: Trace) {
+        if let Some(algorithm) = trace.known_algorithm() {
+            self.selected_algorithm = algorithm;
+            if let Some(values) = trace.known_parameters(algorithm) {
+                self.settings.set_values(algorithm, values);
+            }
+        }
+        self.set_input(trace.input.clone());
+        self.user_input = self
+            .numbers
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        self.shuffling = false;
+        self.session.load(Box::new(TracePlayer::with_trace(trace)));
+        self.clear_spacetime();
         self.reset();
     }
 
+    /// Forgets the rows of the space-time diagram, so the next step starts a new one.
+    fn clear_spacetime(&mut self) {
+        if let Some(spacetime) = &mut self.spacetime {
+            spacetime.clear();
+        }
+    }
+
+    /// Shows the space-time diagram of the run in a panel below the bars.
+    fn show_spacetime(&mut self, ctx: &egui::Context) {
+        let Some(spacetime) = &self.spacetime else {
+            return;
+        };
+        let mut close = false;
+        let mut export = false;
+        egui::TopBottomPanel::bottom("spacetime_panel")
+            .resizable(true)
+            .default_height(220.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.strong("🌈 Space-time");
+                    export = ui
+                        .add_enabled(self.job.is_none(), Button::new("💾 Export PNG"))
+                        .clicked();
+                    close = ui.button("Close").clicked();
+                });
+                panels::draw_spacetime(ui, spacetime, self.selected_theme);
+            });
+        if export {
+            self.export_spacetime();
+        }
+        if close {
+            self.spacetime = None;
+        }
+    }
+
+    /// Asks for a file name, then runs the selected algorithm headless from the original
+    /// numbers in the background and saves the space-time diagram of the whole sort there as PNG.
+    fn export_spacetime(&mut self) {
+        let Some(path) = FileDialog::new()
+            .add_filter("PNG Images", &["png"])
+            .set_file_name("spacetime.png")
+            .save_file()
+        else {
+            return;
+        };
+        let mut sorter = self.settings.create_sorter(self.selected_algorithm);
+        let limit = input_limit(sorter.as_ref(), self.original_numbers.len());
+        let numbers = self.original_numbers[..limit].to_vec();
+        self.start_job("Exporting space-time diagram", move |_| {
+            let spacetime = Spacetime::run(&numbers, sorter.as_mut());
+            let result = fs::File::create(&path)
+                .and_then(|file| export::write_png(file, &spacetime.to_canvas(800)));
+            if let Err(error) = result {
+                eprintln!(
+                    "Failed to export space-time diagram to {}: {}",
+                    path.display(),
+                    error
+                );
+            }
+            Outcome::Saved
+        });
+    }
+
+    /// Draws a gauge of the extra memory the sorter holds, relative to the array length.
+    fn draw_memory_gauge(&self, ui: &mut Ui) {
+        let memory = self.view.auxiliary_memory;
+        let len = self.numbers.len().max(1);
+        ui.label(egui::RichText::new("🧠 Extra memory:").color(self.selected_theme.text_color()));
+        ui.add(
+            egui::ProgressBar::new((memory as f32 / len as f32).min(1.0))
+                .desired_width(90.0)
+                .text(format!("{} / {}", memory, len)),
+        );
+    }
+
+    /// Asks for a file name and saves benchmark results there as CSV.
+    fn export_benchmark(results: &[BenchmarkResult]) {
+        if let Some(path) = FileDialog::new()
+            .add_filter("CSV Files", &["csv"])
+            .set_file_name("benchmark.csv")
+            .save_file()
+        {
+            if let Err(error) = fs::write(&path, benchmark::to_csv(results)) {
+                eprintln!(
+                    "Failed to export benchmark to {}: {}",
+                    path.display(),
+                    error
+                );
+            }
+        }
+    }
+
     /// Creates the control buttons for the visualizer (Start, Step, Reset, Shuffle).
     fn create_control_buttons(&mut self, ui: &mut Ui) {
         if self.state == State::Running {
             if ui.add(Button::new("Stop")).clicked() {
-                self.state = State::Start;
+                ButtonHandler::handle_stop(self);
             }
             ui.add_enabled(false, Button::new("Step"));
         } else {
             if ui.add(Button::new("Start")).clicked() {
-                self.state = State::Running;
-                self.start_time = Some(Instant::now());
+                ButtonHandler::handle_start(self);
             }
             if ui.add(Button::new("Step")).clicked() {
                 ButtonHandler::handle_step(self);
@@ -196,20 +715,81 @@ impl Visualizer<'_> {
         }
     }
 
-    /// Handles continuous sorting steps while in the "Running" state.
+    /// Applies the requests received by the control API since the last frame,
+    /// the way the matching controls would, and answers those the session has caught up with.
+    fn handle_api(&mut self) {
+        let Some(api) = &self.api else {
+            return;
+        };
+        let requests: Vec<Request> = api.requests().collect();
+        for request in requests {
+            match self.apply_command(&request.command) {
+                Ok(()) => self.replies.push((self.session.sent(), request)),
+                Err(message) => request.reply(Err(message)),
+            }
+        }
+        // Answer once the session has handled the commands, so the reply shows their effect
+        let handled = self.session.handled();
+        let (ready, waiting) = mem::take(&mut self.replies)
+            .into_iter()
+            .partition(|(sent, _)| *sent <= handled);
+        self.replies = waiting;
+        for (_, request) in ready {
+            request.reply(Ok(self.status()));
+        }
+    }
+
+    /// Applies one command of the control API.
+    /// # Returns
+    /// Why the command was refused, if it was.
+    fn apply_command(&mut self, command: &Command) -> Result<(), String> {
+        match command {
+            Command::Status => {}
+            Command::Numbers(numbers) => {
+                self.user_input = numbers
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                self.process_user_input();
+                ButtonHandler::handle_reset(self);
+            }
+            Command::Algorithm(algorithm) => {
+                self.selected_algorithm = *algorithm;
+                self.switch_algorithm();
+            }
+            Command::Start => ButtonHandler::handle_start(self),
+            Command::Stop => ButtonHandler::handle_stop(self),
+            Command::Step if self.state == State::Running => {
+                return Err("cannot step while running, stop first".to_string());
+            }
+            Command::Step => ButtonHandler::handle_step(self),
+            Command::Reset => ButtonHandler::handle_reset(self),
+            Command::Shuffle => ButtonHandler::handle_shuffle(self),
+        }
+        Ok(())
+    }
+
+    /// Returns the state reported by the control API.
+    fn status(&self) -> Status {
+        Status {
+            algorithm: format!("{:?}", self.selected_algorithm),
+            state: format!("{:?}", self.state),
+            numbers: self.numbers.clone(),
+            comparisons: self.view.comparisons,
+            swaps: self.view.swaps,
+            writes: self.view.writes,
+            elapsed: self.total_elapsed_time,
+        }
+    }
+
+    /// Tracks the elapsed time while in the "Running" state.
     fn handle_running(&mut self) {
         if self.state == State::Running {
-            thread::sleep(STEP_DELAY);
-            ButtonHandler::handle_step(self);
-
-            // Update the elapsed time and check if the sorting is finished
+            // Update the elapsed time, the session takes the steps
             if let Some(start) = self.start_time {
                 self.total_elapsed_time = start.elapsed().as_secs_f64(); // Time in seconds
             }
-
-            if self.sorter.is_finished() {
-                self.state = State::Finished;
-            }
         }
     }
 
@@ -222,8 +802,7 @@ impl Visualizer<'_> {
             .collect();
 
         if !new_numbers.is_empty() {
-            self.numbers = new_numbers.clone();
-            self.original_numbers = new_numbers;
+            self.set_input(new_numbers);
             self.state = State::Start; // Reset state
         }
     }
@@ -240,8 +819,7 @@ impl Visualizer<'_> {
                     .collect();
 
                 if !new_numbers.is_empty() {
-                    self.numbers = new_numbers.clone();
-                    self.original_numbers = new_numbers;
+                    self.set_input(new_numbers);
                     self.user_input = self
                         .numbers
                         .iter()
@@ -266,8 +844,7 @@ impl Visualizer<'_> {
                     .collect();
 
                 if !new_numbers.is_empty() {
-                    self.numbers = new_numbers.clone();
-                    self.original_numbers = new_numbers;
+                    self.set_input(new_numbers);
                     self.user_input = self
                         .numbers
                         .iter()
@@ -279,18 +856,58 @@ impl Visualizer<'_> {
         }
     }
 
+    /// Makes `numbers` the input and sorts them from the start.
+    /// The session cuts them to what the current algorithm can sort within `STEP_BUDGET`.
+    fn set_input(&mut self, numbers: Vec<usize>) {
+        self.numbers = numbers.clone();
+        self.original_numbers = numbers.clone();
+        self.session.set_input(numbers);
+    }
+
+    /// Applies the updates the session posted since the last frame.
+    fn handle_updates(&mut self) {
+        while let Some(update) = self.session.next_update() {
+            if let (Some(spacetime), Some(_)) = (&mut self.spacetime, &update.event) {
+                spacetime.start(&self.numbers);
+                spacetime.record(&update.numbers, update.finished);
+            }
+            if let (Some(api), Some(event)) = (&self.api, &update.event) {
+                api.publish(event);
+            }
+            self.numbers = update.numbers;
+            self.view = update.view;
+            if update.finished {
+                if self.shuffling {
+                    self.finish_shuffle();
+                    continue;
+                }
+                self.state = State::Finished;
+                self.reset();
+            }
+        }
+    }
+
     /// Resets the visualizer state and timer.
     fn reset(&mut self) {
+        // Resetting abandons an animated shuffle halfway, keeping what it has shuffled so far.
+        if self.shuffling {
+            self.shuffling = false;
+            self.session
+                .load(self.settings.create_sorter(self.selected_algorithm));
+        }
         self.state = State::Start;
-        self.sorter.reset_state();
+        self.session.reset();
         self.start_time = None;
         self.total_elapsed_time = 0.0;
     }
 }
 
-impl eframe::App for Visualizer<'_> {
+impl eframe::App for Visualizer {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         ctx.request_repaint(); // UI refresh request
+        self.handle_updates();
+        self.handle_api();
+        self.handle_job();
 
         let mut style = (*ctx.style()).clone();
         style.visuals.panel_fill = self.selected_theme.background_color();
@@ -330,9 +947,90 @@ impl eframe::App for Visualizer<'_> {
                     if ui.button("📊 Load from CSV").clicked() {
                         self.load_numbers_from_csv();
                     }
-                    let (comparisons, swaps) = (self.sorter.comparisons(), self.sorter.swaps());
+
+                    let idle = self.job.is_none();
+                    if ui.add_enabled(idle, Button::new("📈 Benchmark")).clicked() {
+                        self.run_benchmark();
+                    }
+
+                    if ui.button("🎞 Export animation").clicked() {
+                        self.animation_export = Some(ExportOptions::default());
+                    }
+
+                    if ui.button("📜 Open trace").clicked() {
+                        self.open_trace();
+                    }
+
+                    if ui.add_enabled(idle, Button::new("📝 Save trace")).clicked() {
+                        self.save_trace();
+                    }
+
+                    if ui.button("🖼 Export SVG").clicked() {
+                        self.export_svg();
+                    }
+
+                    if ui.button("🌈 Space-time").clicked() {
+                        self.spacetime = match self.spacetime {
+                            Some(_) => None,
+                            None => Some(Spacetime::default()),
+                        };
+                    }
+
+                    if ui
+                        .add_enabled(idle, Button::new("🎲 Shuffle stats"))
+                        .clicked()
+                    {
+                        let method = self.selected_shuffle.unwrap_or(ShuffleMethod::Naive);
+                        self.run_shuffle_stats(method, 8, 10_000);
+                    }
+                    self.draw_job(ui);
+                    let (comparisons, swaps, writes) =
+                        (self.view.comparisons, self.view.swaps, self.view.writes);
 
                     ui.horizontal(|ui| {
+                        // Warn before starting an algorithm that needs a huge number of steps.
+                        let estimate = self.view.estimated_steps;
+                        if let (true, Some(steps)) = (self.state == State::Start, estimate) {
+                            let steps = if steps < 1e6 {
+                                format!("{:.0}", steps)
+                            } else {
+                                format!("{:.2e}", steps)
+                            };
+                            ui.label(
+                                egui::RichText::new(format!("⏳ Estimated steps: {} ", steps))
+                                    .color(self.selected_theme.text_color()),
+                            );
+                        }
+                        if self.numbers.len() < self.original_numbers.len() {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "✂ Capped to {} of {} elements ",
+                                    self.numbers.len(),
+                                    self.original_numbers.len()
+                                ))
+                                .color(self.selected_theme.text_color()),
+                            );
+                        }
+                        // Tell whether a selection or top-k algorithm has finalized its target.
+                        if let Some((low, high)) = self.view.target {
+                            let finalized = &self.view.finalized;
+                            let done = (low..high).all(|index| finalized.contains(&index));
+                            ui.label(
+                                egui::RichText::new(if done {
+                                    "🎯 Target finalized "
+                                } else {
+                                    "🎯 Target pending "
+                                })
+                                .color(self.selected_theme.text_color()),
+                            );
+                        }
+                        // Show which strategy a hybrid algorithm is currently using.
+                        if let Some(phase) = self.view.phase {
+                            ui.label(
+                                egui::RichText::new(format!("⚙ {} ", phase))
+                                    .color(self.selected_theme.text_color()),
+                            );
+                        }
                         ui.label(
                             egui::RichText::new(format!("🔍 Comparisons: {} ", comparisons))
                                 .color(self.selected_theme.text_color()),
@@ -341,6 +1039,11 @@ impl eframe::App for Visualizer<'_> {
                             egui::RichText::new(format!("🔄 Swaps: {} ", swaps))
                                 .color(self.selected_theme.text_color()),
                         );
+                        ui.label(
+                            egui::RichText::new(format!("✏ Writes: {} ", writes))
+                                .color(self.selected_theme.text_color()),
+                        );
+                        self.draw_memory_gauge(ui);
                         ui.label(
                             egui::RichText::new(format!(
                                 "Elapsed Time: {:.2}s",
@@ -352,13 +1055,31 @@ impl eframe::App for Visualizer<'_> {
                 });
             });
         });
+        self.show_benchmark(ctx);
+        self.show_shuffle_stats(ctx);
+        self.show_animation_export(ctx);
+
+        // Auxiliary structure of the current algorithm, if it exposes one
+        if let Some(structure) = &self.view.structure {
+            egui::SidePanel::right("structure_panel")
+                .default_width(420.0)
+                .show(ctx, |ui| {
+                    panels::draw_structure(ui, structure, self.selected_theme);
+                });
+        }
+
+        self.show_spacetime(ctx);
+
         // Main sorting UI and visualization
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.horizontal(|ui| {
                 if self.handle_algorithm_selection(ui) {
                     self.switch_algorithm();
                 }
+                self.handle_parameters(ui);
                 self.handle_theme_selection(ui);
+                self.handle_distribution_selection(ui);
+                self.handle_shuffle_selection(ui);
                 self.create_control_buttons(ui);
             });
 
@@ -367,3 +1088,86 @@ impl eframe::App for Visualizer<'_> {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{SocketAddr, TcpStream};
+    use std::thread;
+
+    /// Sends a request and returns the status code and body of the response.
+    fn request(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let code = response[9..12].parse().unwrap();
+        let body = response.split("\r\n\r\n").nth(1).unwrap_or_default();
+        (code, body.to_string())
+    }
+
+    #[test]
+    fn scripts_drive_the_visualizer_through_the_api() {
+        let api = Api::start(0).unwrap();
+        let address = api.address();
+        let defaults = Visualizer::default();
+        let mut app = Visualizer {
+            api: Some(api),
+            session: Session::start(Box::new(BubbleSort::new()), defaults.numbers.clone(), true),
+            ..defaults
+        };
+
+        let client = thread::spawn(move || {
+            let (code, body) = request(address, "POST", "/numbers", "[3, 1, 2]");
+            assert_eq!(code, 200, "{}", body);
+            assert!(body.contains(r#""numbers":[3,1,2]"#), "{}", body);
+            let (code, body) = request(address, "POST", "/algorithm", "insertion");
+            assert_eq!(code, 200, "{}", body);
+            assert!(body.contains(r#""algorithm":"Insertion""#), "{}", body);
+
+            let mut events = TcpStream::connect(address).unwrap();
+            write!(events, "GET /events HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            let mut events = BufReader::new(events);
+            let mut line = String::new();
+            while line != "\r\n" {
+                line.clear();
+                events.read_line(&mut line).unwrap();
+            }
+
+            let (code, body) = request(address, "POST", "/step", "");
+            assert_eq!(code, 200, "{}", body);
+            assert!(body.contains(r#""comparisons":1"#), "{}", body);
+            // Skip heartbeats, in case the stream was idle for a while.
+            while line.trim().is_empty() {
+                line.clear();
+                events.read_line(&mut line).unwrap();
+            }
+            let event: crate::trace::Event = serde_json::from_str(&line).unwrap();
+            assert_eq!(event.comparisons, 1);
+
+            let (code, _) = request(address, "POST", "/algorithm", "sleepy");
+            assert_eq!(code, 400);
+            let (code, _) = request(address, "GET", "/step", "");
+            assert_eq!(code, 405);
+            let (code, _) = request(address, "GET", "/nowhere", "");
+            assert_eq!(code, 404);
+        });
+
+        while !client.is_finished() {
+            app.handle_updates();
+            app.handle_api();
+            thread::sleep(std::time::Duration::from_millis(1));
+        }
+        client.join().unwrap();
+        assert_eq!(app.numbers, [1, 3, 2]);
+    }
+}
@@ -0,0 +1,592 @@
+use super::constants::Theme;
+use super::spacetime::Spacetime;
+use crate::algorithms::{
+    smooth_sort::leonardo_children,
+    structure::Structure,
+    top_down_merge_sort::{Frame, FrameState},
+    tree_sort::Node,
+};
+use crate::shuffle::ShuffleStats;
+use eframe::{
+    egui::{self, RichText, Sense, Ui},
+    epaint::{pos2, Color32, Stroke},
+};
+
+/// Draws the auxiliary data structure exposed by the current sorter.
+pub(crate) fn draw_structure(ui: &mut Ui, structure: &Structure, theme: Theme) {
+    match structure {
+        Structure::Network {
+            wires,
+            layers,
+            current,
+        } => draw_network(ui, *wires, layers, *current, theme),
+        Structure::LeonardoForest {
+            len,
+            heaps,
+            sorted_from,
+            focus,
+        } => draw_leonardo_forest(ui, *len, heaps, *sorted_from, *focus, theme),
+        Structure::Buckets {
+            buckets,
+            lower_bounds,
+            current,
+        } => draw_buckets(ui, buckets, lower_bounds, *current, theme),
+        Structure::SearchTree { nodes, focus } => draw_search_tree(ui, nodes, *focus, theme),
+        Structure::Piles {
+            piles,
+            focus,
+            dealing,
+        } => draw_piles(ui, piles, *focus, *dealing, theme),
+        Structure::RecursionTree {
+            len,
+            frames,
+            current,
+            stack_depth,
+        } => draw_recursion_tree(ui, *len, frames, *current, *stack_depth, theme),
+    }
+}
+
+/// Draws a Knuth-style sorting network diagram.
+/// Wires run left to right, comparators are vertical segments grouped by layer,
+/// and the layer executed by the last step is highlighted.
+fn draw_network(
+    ui: &mut Ui,
+    wires: usize,
+    layers: &[Vec<(usize, usize)>],
+    current: Option<usize>,
+    theme: Theme,
+) {
+    let comparators: usize = layers.iter().map(Vec::len).sum();
+    ui.label(
+        RichText::new(format!(
+            "Network: {} wires, {} layers, {} comparators",
+            wires,
+            layers.len(),
+            comparators
+        ))
+        .color(theme.text_color()),
+    );
+
+    // Split every layer into columns whose comparators do not overlap vertically.
+    let columns: Vec<(usize, Vec<(usize, usize)>)> = layers
+        .iter()
+        .enumerate()
+        .flat_map(|(layer_index, layer)| {
+            let mut columns: Vec<Vec<(usize, usize)>> = Vec::new();
+            for &(i, j) in layer {
+                let free = columns
+                    .iter_mut()
+                    .find(|column| column.iter().all(|&(a, b)| j < a || i > b));
+                match free {
+                    Some(column) => column.push((i, j)),
+                    None => columns.push(vec![(i, j)]),
+                }
+            }
+            columns.into_iter().map(move |column| (layer_index, column))
+        })
+        .collect();
+
+    let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::hover());
+    let rect = response.rect.shrink(8.0);
+    let wire_gap = rect.height() / wires.max(1) as f32;
+    let wire_y = |wire: usize| rect.top() + (wire as f32 + 0.5) * wire_gap;
+
+    for wire in 0..wires {
+        painter.line_segment(
+            [
+                pos2(rect.left(), wire_y(wire)),
+                pos2(rect.right(), wire_y(wire)),
+            ],
+            Stroke::new(1.0, theme.bar_color()),
+        );
+    }
+
+    // Leave an extra column of space between layers so they read as groups.
+    let slots = columns.len() + layers.len();
+    let column_width = rect.width() / slots.max(1) as f32;
+    let radius = (wire_gap / 2.0).clamp(1.0, 3.0);
+    let mut x = rect.left() + column_width / 2.0;
+    let mut previous_layer = None;
+
+    for (layer_index, column) in &columns {
+        if previous_layer.is_some() && previous_layer != Some(*layer_index) {
+            x += column_width;
+        }
+        previous_layer = Some(*layer_index);
+
+        let (color, width) = match current {
+            Some(current) if current == *layer_index => (Color32::LIGHT_YELLOW, 2.5),
+            Some(current) if current > *layer_index => {
+                (theme.text_color().gamma_multiply(0.4), 1.0)
+            }
+            _ => (theme.text_color(), 1.0),
+        };
+
+        for &(i, j) in column {
+            let (top, bottom) = (pos2(x, wire_y(i)), pos2(x, wire_y(j)));
+            painter.line_segment([top, bottom], Stroke::new(width, color));
+            painter.circle_filled(top, radius, color);
+            painter.circle_filled(bottom, radius, color);
+        }
+        x += column_width;
+    }
+
+    if let Some(current) = current {
+        painter.text(
+            response.rect.left_bottom(),
+            egui::Align2::LEFT_BOTTOM,
+            format!("Layer {} of {}", current + 1, layers.len()),
+            egui::FontId::default(),
+            theme.text_color(),
+        );
+    }
+}
+
+/// Draws SmoothSort's forest of Leonardo heaps.
+/// Every node sits above its array index, so each heap spans the bars it covers,
+/// and the sorted suffix is shaded.
+fn draw_leonardo_forest(
+    ui: &mut Ui,
+    len: usize,
+    heaps: &[(usize, usize)],
+    sorted_from: usize,
+    focus: (usize, usize),
+    theme: Theme,
+) {
+    let orders = heaps
+        .iter()
+        .map(|(_, order)| order.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    ui.label(
+        RichText::new(format!("Leonardo heaps (orders): [{}]", orders)).color(theme.text_color()),
+    );
+
+    let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::hover());
+    let rect = response.rect.shrink(8.0);
+    let column = rect.width() / len.max(1) as f32;
+    let x = |index: usize| rect.left() + (index as f32 + 0.5) * column;
+
+    // A heap of order k is k - 1 levels deep, so the largest order sets the level height.
+    let levels = heaps
+        .iter()
+        .map(|&(_, order)| order)
+        .max()
+        .unwrap_or(1)
+        .max(1);
+    let level_height = (rect.height() / levels as f32).min(40.0);
+    let y = |depth: usize| rect.top() + 10.0 + depth as f32 * level_height;
+    let radius = (column / 2.0).clamp(2.0, 6.0);
+
+    if sorted_from < len {
+        let shade = egui::Rect::from_min_max(
+            pos2(x(sorted_from) - column / 2.0, rect.top()),
+            rect.right_bottom(),
+        );
+        painter.rect_filled(shade, 4.0, Color32::LIGHT_GREEN.gamma_multiply(0.2));
+    }
+
+    // Walk every heap iteratively, drawing edges before nodes.
+    let mut nodes = Vec::new();
+    for &(root, order) in heaps {
+        let mut stack = vec![(root, order, 0)];
+        while let Some((node, order, depth)) = stack.pop() {
+            nodes.push((node, depth, depth == 0));
+            if let Some(((left, left_order), (right, right_order))) = leonardo_children(node, order)
+            {
+                for child in [left, right] {
+                    painter.line_segment(
+                        [pos2(x(node), y(depth)), pos2(x(child), y(depth + 1))],
+                        Stroke::new(1.0, theme.text_color().gamma_multiply(0.6)),
+                    );
+                }
+                stack.push((left, left_order, depth + 1));
+                stack.push((right, right_order, depth + 1));
+            }
+        }
+    }
+
+    for (node, depth, is_root) in nodes {
+        let color = if node == focus.0 || node == focus.1 {
+            Color32::LIGHT_YELLOW
+        } else if is_root {
+            Color32::LIGHT_RED
+        } else {
+            theme.bar_color()
+        };
+        painter.circle_filled(pos2(x(node), y(depth)), radius, color);
+    }
+}
+
+/// Draws a histogram of bucket sizes with each bucket's lower bound underneath.
+/// A dashed line marks the size every bucket would have if the input were spread evenly,
+/// which makes the imbalance caused by skewed inputs visible.
+fn draw_buckets(
+    ui: &mut Ui,
+    buckets: &[Vec<usize>],
+    lower_bounds: &[usize],
+    current: Option<usize>,
+    theme: Theme,
+) {
+    let total: usize = buckets.iter().map(Vec::len).sum();
+    let largest = buckets.iter().map(Vec::len).max().unwrap_or(0);
+    let ideal = total as f32 / buckets.len().max(1) as f32;
+    ui.label(
+        RichText::new(format!(
+            "{} elements in {} buckets, largest holds {} (even spread: {:.1})",
+            total,
+            buckets.len(),
+            largest,
+            ideal
+        ))
+        .color(theme.text_color()),
+    );
+
+    let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::hover());
+    let rect = response.rect.shrink(8.0);
+    let label_height = 20.0;
+    let plot_bottom = rect.bottom() - label_height;
+    let column = rect.width() / buckets.len().max(1) as f32;
+    let scale = (plot_bottom - rect.top()) / largest.max(1) as f32;
+
+    for (i, bucket) in buckets.iter().enumerate() {
+        let left = rect.left() + i as f32 * column;
+        let height = bucket.len() as f32 * scale;
+        let color = if current == Some(i) {
+            Color32::LIGHT_YELLOW
+        } else {
+            theme.bar_color()
+        };
+        let bar = egui::Rect::from_min_max(
+            pos2(left + 2.0, plot_bottom - height),
+            pos2(left + column - 2.0, plot_bottom),
+        );
+        painter.rect_filled(bar, 2.0, color);
+        painter.text(
+            pos2(left + column / 2.0, plot_bottom - height - 2.0),
+            egui::Align2::CENTER_BOTTOM,
+            bucket.len().to_string(),
+            egui::FontId::proportional(11.0),
+            theme.text_color(),
+        );
+        painter.text(
+            pos2(left + column / 2.0, rect.bottom()),
+            egui::Align2::CENTER_BOTTOM,
+            format!("≥{}", lower_bounds[i]),
+            egui::FontId::proportional(11.0),
+            theme.text_color(),
+        );
+    }
+
+    let ideal_y = plot_bottom - ideal * scale;
+    painter.add(egui::Shape::dashed_line(
+        &[pos2(rect.left(), ideal_y), pos2(rect.right(), ideal_y)],
+        Stroke::new(1.0, Color32::LIGHT_RED),
+        6.0,
+        4.0,
+    ));
+}
+
+/// Draws TreeSort's binary search tree.
+/// Nodes are placed left to right in sorted (in-order) order and top to bottom by depth,
+/// values already written back are dimmed, and the node touched by the last step is highlighted.
+fn draw_search_tree(ui: &mut Ui, nodes: &[Node], focus: Option<usize>, theme: Theme) {
+    // In-order traversal assigns each node its column and depth.
+    let mut position = vec![(0, 0); nodes.len()];
+    let mut stack = Vec::new();
+    let mut cursor = Some((0, 0));
+    let mut column = 0;
+    let mut height = 0;
+    loop {
+        while let Some((node, depth)) = cursor {
+            stack.push((node, depth));
+            cursor = nodes[node].left.map(|left| (left, depth + 1));
+        }
+        let Some((node, depth)) = stack.pop() else {
+            break;
+        };
+        position[node] = (column, depth);
+        column += 1;
+        height = height.max(depth + 1);
+        cursor = nodes[node].right.map(|right| (right, depth + 1));
+    }
+
+    let written = nodes.iter().filter(|node| node.written).count();
+    ui.label(
+        RichText::new(format!(
+            "Search tree: {} nodes, height {}, {} written back",
+            nodes.len(),
+            height,
+            written
+        ))
+        .color(theme.text_color()),
+    );
+
+    let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::hover());
+    let rect = response.rect.shrink(10.0);
+    let column_width = rect.width() / nodes.len().max(1) as f32;
+    let level_height = (rect.height() / height.max(1) as f32).min(40.0);
+    let center = |node: usize| {
+        let (column, depth) = position[node];
+        pos2(
+            rect.left() + (column as f32 + 0.5) * column_width,
+            rect.top() + 8.0 + depth as f32 * level_height,
+        )
+    };
+    let radius = (column_width / 2.0).clamp(2.0, 8.0);
+
+    for (index, node) in nodes.iter().enumerate() {
+        for child in [node.left, node.right].into_iter().flatten() {
+            painter.line_segment(
+                [center(index), center(child)],
+                Stroke::new(1.0, theme.text_color().gamma_multiply(0.6)),
+            );
+        }
+    }
+    for (index, node) in nodes.iter().enumerate() {
+        let color = if focus == Some(index) {
+            Color32::LIGHT_YELLOW
+        } else if node.written {
+            theme.bar_color().gamma_multiply(0.3)
+        } else {
+            theme.bar_color()
+        };
+        painter.circle_filled(center(index), radius, color);
+        // Labels only fit while the nodes are reasonably wide.
+        if column_width >= 18.0 {
+            painter.text(
+                center(index) + egui::vec2(0.0, radius + 1.0),
+                egui::Align2::CENTER_TOP,
+                node.value.to_string(),
+                egui::FontId::proportional(10.0),
+                theme.text_color(),
+            );
+        }
+    }
+}
+
+/// Draws PatienceSort's piles as columns of cards, bottom card first.
+/// The pile touched by the last step has its top card highlighted.
+fn draw_piles(
+    ui: &mut Ui,
+    piles: &[Vec<usize>],
+    focus: Option<usize>,
+    dealing: bool,
+    theme: Theme,
+) {
+    let remaining: usize = piles.iter().map(Vec::len).sum();
+    let action = if dealing { "dealt" } else { "left to merge" };
+    ui.label(
+        RichText::new(format!(
+            "{} piles, {} cards {}",
+            piles.len(),
+            remaining,
+            action
+        ))
+        .color(theme.text_color()),
+    );
+
+    let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::hover());
+    let rect = response.rect.shrink(8.0);
+    let column = rect.width() / piles.len().max(1) as f32;
+    let tallest = piles.iter().map(Vec::len).max().unwrap_or(1).max(1);
+    let card_height = (rect.height() / tallest as f32).min(22.0);
+
+    for (index, pile) in piles.iter().enumerate() {
+        let left = rect.left() + index as f32 * column;
+        for (depth, &card) in pile.iter().enumerate() {
+            let top = rect.top() + depth as f32 * card_height;
+            let card_rect = egui::Rect::from_min_size(
+                pos2(left + 2.0, top),
+                egui::vec2(column - 4.0, card_height - 2.0),
+            );
+            let color = if focus == Some(index) && depth + 1 == pile.len() {
+                if dealing {
+                    Color32::LIGHT_YELLOW
+                } else {
+                    Color32::LIGHT_GREEN
+                }
+            } else {
+                theme.bar_color()
+            };
+            painter.rect_filled(card_rect, 2.0, color);
+            if card_height >= 12.0 && column >= 18.0 {
+                painter.text(
+                    card_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    card.to_string(),
+                    egui::FontId::proportional(10.0),
+                    theme.background_color(),
+                );
+            }
+        }
+    }
+}
+
+/// Draws the recursion tree of the top-down MergeSort.
+/// Each frame is a box spanning the indices it covers, one row per recursion depth,
+/// so the boxes line up with the bars. Finished frames are green, frames split and
+/// waiting for their halves are outlined, and the frame handled by the last step is yellow.
+fn draw_recursion_tree(
+    ui: &mut Ui,
+    len: usize,
+    frames: &[Frame],
+    current: Option<usize>,
+    stack_depth: usize,
+    theme: Theme,
+) {
+    let done = frames
+        .iter()
+        .filter(|frame| frame.state == FrameState::Done)
+        .count();
+    ui.label(
+        RichText::new(format!(
+            "Recursion tree: {} frames, {} done, {} on the stack",
+            frames.len(),
+            done,
+            stack_depth
+        ))
+        .color(theme.text_color()),
+    );
+
+    let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::hover());
+    let rect = response.rect.shrink(8.0);
+    let depth = frames.iter().map(|frame| frame.depth).max().unwrap_or(0) + 1;
+    let row = (rect.height() / depth as f32).min(28.0);
+    let column = rect.width() / len.max(1) as f32;
+
+    for (id, frame) in frames.iter().enumerate() {
+        let frame_rect = egui::Rect::from_min_max(
+            pos2(
+                rect.left() + frame.low as f32 * column + 1.0,
+                rect.top() + frame.depth as f32 * row + 1.0,
+            ),
+            pos2(
+                rect.left() + frame.high as f32 * column - 1.0,
+                rect.top() + (frame.depth + 1) as f32 * row - 2.0,
+            ),
+        );
+        if current == Some(id) {
+            painter.rect_filled(frame_rect, 2.0, Color32::LIGHT_YELLOW);
+        } else {
+            match frame.state {
+                FrameState::Done => {
+                    painter.rect_filled(frame_rect, 2.0, Color32::LIGHT_GREEN.gamma_multiply(0.5))
+                }
+                FrameState::Split => {
+                    painter.rect_stroke(frame_rect, 2.0, Stroke::new(1.5, theme.bar_color()))
+                }
+                FrameState::Waiting => painter.rect_stroke(
+                    frame_rect,
+                    2.0,
+                    Stroke::new(1.0, theme.text_color().gamma_multiply(0.4)),
+                ),
+            }
+        }
+        if frame_rect.width() >= 40.0 {
+            painter.text(
+                frame_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                format!("{}..{}", frame.low, frame.high),
+                egui::FontId::proportional(10.0),
+                theme.text_color(),
+            );
+        }
+    }
+}
+
+/// Draws how often each value landed at each position over many shuffles.
+/// Rows are positions and columns are values. Cells hit as often as an unbiased shuffle
+/// would hit them are grey, cells hit more often shade towards red and less often towards blue.
+pub(crate) fn draw_heatmap(ui: &mut Ui, stats: &ShuffleStats, theme: Theme) {
+    ui.label(
+        RichText::new(format!(
+            "{} shuffles of {} elements, {:.0} expected per cell, largest deviation {:.1}%",
+            stats.trials,
+            stats.size,
+            stats.expected(),
+            stats.max_deviation() * 100.0
+        ))
+        .color(theme.text_color()),
+    );
+
+    let side = ui.available_width().min(ui.available_height()).max(120.0);
+    let (response, painter) = ui.allocate_painter(egui::vec2(side, side), Sense::hover());
+    let rect = response.rect.shrink(4.0);
+    let cell = rect.width() / stats.size.max(1) as f32;
+    let expected = stats.expected();
+
+    // Blend from grey towards `to` by `amount` in 0..=1.
+    let blend = |to: Color32, amount: f32| {
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * amount) as u8;
+        let grey = 128;
+        Color32::from_rgb(mix(grey, to.r()), mix(grey, to.g()), mix(grey, to.b()))
+    };
+
+    for (position, row) in stats.counts.iter().enumerate() {
+        for (value, &count) in row.iter().enumerate() {
+            let ratio = count as f64 / expected;
+            // A deviation of 50% saturates the color.
+            let amount = ((ratio - 1.0).abs() * 2.0).min(1.0) as f32;
+            let color = if ratio >= 1.0 {
+                blend(Color32::from_rgb(230, 60, 60), amount)
+            } else {
+                blend(Color32::from_rgb(60, 110, 230), amount)
+            };
+            let cell_rect = egui::Rect::from_min_size(
+                pos2(
+                    rect.left() + value as f32 * cell,
+                    rect.top() + position as f32 * cell,
+                ),
+                egui::vec2(cell - 1.0, cell - 1.0),
+            );
+            painter.rect_filled(cell_rect, 1.0, color);
+            if cell >= 30.0 {
+                painter.text(
+                    cell_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    format!("{:.0}%", ratio * 100.0),
+                    egui::FontId::proportional(10.0),
+                    Color32::WHITE,
+                );
+            }
+        }
+    }
+}
+
+/// Draws the recorded rows of a space-time diagram, one thin row per step and one column
+/// per position, colored by value. It follows the newest row while the sort runs.
+pub(crate) fn draw_spacetime(ui: &mut Ui, spacetime: &Spacetime, theme: Theme) {
+    let stride = if spacetime.stride() > 1 {
+        format!(", one row every {} steps", spacetime.stride())
+    } else {
+        String::new()
+    };
+    ui.label(
+        RichText::new(format!("{} steps{}", spacetime.steps(), stride)).color(theme.text_color()),
+    );
+
+    let rows = spacetime.rows();
+    let columns = rows.first().map_or(1, Vec::len).max(1);
+    let row_height = 2.0;
+    ui.spacing_mut().item_spacing.y = 0.0;
+    egui::ScrollArea::vertical()
+        .auto_shrink([false; 2])
+        .stick_to_bottom(true)
+        .show_rows(ui, row_height, rows.len(), |ui, visible| {
+            let size = egui::vec2(ui.available_width(), row_height * visible.len() as f32);
+            let (rect, _) = ui.allocate_exact_size(size, Sense::hover());
+            let cell = rect.width() / columns as f32;
+            let painter = ui.painter();
+            for (offset, row) in rows[visible].iter().enumerate() {
+                let top = rect.top() + offset as f32 * row_height;
+                for (position, &value) in row.iter().take(columns).enumerate() {
+                    let cell_rect = egui::Rect::from_min_size(
+                        pos2(rect.left() + position as f32 * cell, top),
+                        egui::vec2(cell, row_height),
+                    );
+                    painter.rect_filled(cell_rect, 0.0, spacetime.color(value));
+                }
+            }
+        });
+}
@@ -0,0 +1,221 @@
+use super::constants::Theme;
+use super::session::SorterView;
+use crate::algorithms::Reasons;
+use eframe::{
+    egui::Align2,
+    epaint::{pos2, vec2, Color32, Pos2, Rect, Shape, Stroke, Vec2},
+};
+
+/// What the highlight colors of the bar view mean, for legends.
+const LEGEND: [(&str, Color32); 7] = [
+    ("Comparing", Color32::LIGHT_YELLOW),
+    ("Switching", Color32::LIGHT_GREEN),
+    ("Pivot", Color32::LIGHT_RED),
+    ("Finalized", Color32::GOLD),
+    ("Linked", Color32::LIGHT_BLUE),
+    ("Active range", ACTIVE_REGION),
+    ("Handed-off range", HANDED_OFF_REGION),
+];
+
+/// Shade of a range a hybrid sorter is working on.
+const ACTIVE_REGION: Color32 = Color32::from_rgb(255, 170, 90);
+
+/// Shade of a range a hybrid sorter has handed to another algorithm.
+const HANDED_OFF_REGION: Color32 = Color32::from_rgb(190, 140, 255);
+
+/// Where the bars of the array are drawn.
+pub(crate) struct Layout {
+    pub left: f32,         // Left edge of the first bar.
+    pub width: f32,        // Width shared by all bars and the spacing between them.
+    pub bottom: f32,       // Bottom edge of every bar.
+    pub graph_height: f32, // Height of the bar of the largest value.
+}
+
+impl Layout {
+    /// Returns the layout of the main window, below the controls.
+    /// # Arguments
+    /// * `size` - The space left in the central panel.
+    pub fn window(size: Vec2) -> Self {
+        let top_ui_height = 150.0;
+        Self {
+            left: 5.0,
+            width: size.x,
+            bottom: size.y + 96.0, // bars align properly at bottom
+            graph_height: (size.y - top_ui_height).max(250.0), // Maximize graph usage
+        }
+    }
+
+    /// Returns the layout filling an image of `width` x `height` pixels,
+    /// leaving room above the bars for the target marker.
+    pub fn image(width: u32, height: u32) -> Self {
+        Self {
+            left: 5.0,
+            width: width as f32 - 10.0,
+            bottom: height as f32,
+            graph_height: height as f32 - 24.0,
+        }
+    }
+}
+
+/// A label of the bar view, drawn by whoever can render text.
+pub(crate) struct Label {
+    pub pos: Pos2,
+    pub align: Align2,
+    pub text: String,
+    pub color: Color32,
+}
+
+/// The bar view of the array as plain shapes, so the window and the exporters draw the same picture.
+pub(crate) struct Scene {
+    pub shapes: Vec<Shape>, // Filled rectangles, line segments and circles, in drawing order.
+    pub labels: Vec<Label>, // Text drawn on top of the shapes.
+    pub legend: Vec<(&'static str, Color32)>, // The highlight colors used, with their meaning.
+}
+
+/// Lays out the bars of `numbers` and the markers of `sorter`.
+/// # Arguments
+/// * `numbers` - The array being sorted.
+/// * `sorter` - The sorter whose highlights, regions, target and links are shown.
+/// * `finished` - Whether the sort is over, which hides everything but the finalized bars.
+/// * `theme` - The theme giving the color of plain bars.
+/// * `layout` - Where the bars go.
+pub(crate) fn bars(
+    numbers: &[usize],
+    sorter: &SorterView,
+    finished: bool,
+    theme: Theme,
+    layout: &Layout,
+) -> Scene {
+    let mut scene = Scene {
+        shapes: Vec::new(),
+        labels: Vec::new(),
+        legend: Vec::new(),
+    };
+    let mut used = Vec::new(); // Colors that may need a legend entry.
+    let num_bars = numbers.len().max(1); // Prevent division by zero
+    let spacing = 5.0; // Reduce space between bars for better fit
+    let total_spacing = spacing * (num_bars - 1) as f32;
+    let bar_width = ((layout.width - total_spacing) / num_bars as f32).max(2.0); // Ensure minimum width
+
+    let max_value = *numbers.iter().max().unwrap_or(&1); // Get max value to scale height
+    let graph_height = layout.graph_height;
+    let bottom = layout.bottom;
+
+    let bar_top = |index: usize| {
+        let x = index as f32 * (bar_width + spacing) + layout.left;
+        let bar_height = ((numbers[index] as f32 / max_value as f32) * graph_height).max(10.0);
+        pos2(x, bottom - bar_height)
+    };
+
+    // Shade the ranges handed between the algorithms of a hybrid sorter
+    if !finished {
+        for region in &sorter.regions {
+            if region.high > numbers.len() || region.low >= region.high {
+                continue;
+            }
+            let color = if region.handed_off {
+                HANDED_OFF_REGION
+            } else {
+                ACTIVE_REGION
+            };
+            let left = bar_top(region.low).x - spacing / 2.0;
+            let right = bar_top(region.high - 1).x + bar_width + spacing / 2.0;
+            let band = Rect::from_min_max(pos2(left, bottom - graph_height), pos2(right, bottom));
+            used.push(color);
+            let alpha = if region.active { 0.3 } else { 0.08 };
+            scene
+                .shapes
+                .push(Shape::rect_filled(band, 4.0, color.gamma_multiply(alpha)));
+            if region.active {
+                scene.labels.push(Label {
+                    pos: band.left_top(),
+                    align: Align2::LEFT_BOTTOM,
+                    text: region.algorithm.clone(),
+                    color,
+                });
+            }
+        }
+    }
+
+    for index in 0..numbers.len() {
+        let top = bar_top(index);
+        let bar_height = bottom - top.y;
+
+        let color = bar_color(index, sorter, finished, theme);
+        let rect = Rect::from_min_size(top, vec2(bar_width, bar_height));
+        if color != theme.bar_color() {
+            used.push(color);
+        }
+
+        scene.shapes.push(Shape::rect_filled(rect, 4.0, color));
+    }
+
+    // Mark the positions a selection or top-k algorithm must finalize
+    if let Some((low, high)) = sorter.target {
+        if low < high && high <= numbers.len() {
+            let y = bottom - graph_height - 8.0;
+            let left = bar_top(low).x;
+            let right = bar_top(high - 1).x + bar_width;
+            let stroke = Stroke::new(2.0, Color32::GOLD);
+            scene
+                .shapes
+                .push(Shape::line_segment([pos2(left, y), pos2(right, y)], stroke));
+            scene.labels.push(Label {
+                pos: pos2((left + right) / 2.0, y - 2.0),
+                align: Align2::CENTER_BOTTOM,
+                text: "🎯 Target".to_string(),
+                color: Color32::GOLD,
+            });
+        }
+    }
+
+    // Connect linked elements through the tops of their bars
+    if !finished {
+        let stroke = Stroke::new(2.0, Color32::LIGHT_BLUE);
+        for &(a, b) in &sorter.links {
+            if a.max(b) >= numbers.len() {
+                continue;
+            }
+            used.push(Color32::LIGHT_BLUE);
+            let center = vec2(bar_width / 2.0, 0.0);
+            let (from, to) = (bar_top(a) + center, bar_top(b) + center);
+            scene.shapes.push(Shape::line_segment([from, to], stroke));
+            scene
+                .shapes
+                .push(Shape::circle_filled(from, 3.0, Color32::LIGHT_BLUE));
+            scene
+                .shapes
+                .push(Shape::circle_filled(to, 3.0, Color32::LIGHT_BLUE));
+        }
+    }
+
+    scene.legend = LEGEND
+        .into_iter()
+        .filter(|(_, color)| used.contains(color))
+        .collect();
+    scene
+}
+
+/// Determines the color of a bar based on the sorting state and indices.
+fn bar_color(index: usize, sorter: &SorterView, finished: bool, theme: Theme) -> Color32 {
+    let highlight = sorter.highlights.iter().find(|(i, _)| *i == index);
+    if !finished && sorter.pivots.contains(&index) {
+        Color32::LIGHT_RED
+    } else if let (false, Some((_, reason))) = (finished, highlight) {
+        reason_color(*reason)
+    } else if !finished && (index == sorter.special.0 || index == sorter.special.1) {
+        reason_color(sorter.reason)
+    } else if sorter.finalized.contains(&index) {
+        Color32::GOLD // Holds its final value while the rest may stay unsorted
+    } else {
+        theme.bar_color() // Bar color based on the selected theme
+    }
+}
+
+/// Returns the highlight color for a sorting action.
+fn reason_color(reason: Reasons) -> Color32 {
+    match reason {
+        Reasons::Comparing => Color32::LIGHT_YELLOW,
+        Reasons::Switching => Color32::LIGHT_GREEN,
+    }
+}
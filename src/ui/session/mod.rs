@@ -0,0 +1,323 @@
+use crate::algorithms::{input_limit, structure::Structure, Reasons, Region, Sorter};
+use crate::sound;
+use crate::trace::Event;
+use crate::types::STEP_DELAY;
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TryRecvError};
+use std::thread;
+
+/// What the window shows of a sorter, copied from it after every step
+/// so it can be drawn while the worker takes the next one.
+pub(crate) struct SorterView {
+    pub special: (usize, usize),
+    pub reason: Reasons,
+    pub comparisons: usize,
+    pub swaps: usize,
+    pub writes: usize,
+    pub auxiliary_memory: usize,
+    pub phase: Option<&'static str>,
+    pub pivots: Vec<usize>,
+    pub highlights: Vec<(usize, Reasons)>,
+    pub links: Vec<(usize, usize)>,
+    pub estimated_steps: Option<f64>, // Estimate for the length of the array the sorter was given.
+    pub target: Option<(usize, usize)>,
+    pub finalized: Vec<usize>,
+    pub regions: Vec<Region>,
+    pub structure: Option<Structure<'static>>,
+}
+
+impl SorterView {
+    /// Copies what the window shows of `sorter`, which sorts `len` elements.
+    pub fn of(sorter: &dyn Sorter, len: usize) -> Self {
+        Self {
+            special: sorter.special(),
+            reason: sorter.reason(),
+            comparisons: sorter.comparisons(),
+            swaps: sorter.swaps(),
+            writes: sorter.writes(),
+            auxiliary_memory: sorter.auxiliary_memory(),
+            phase: sorter.phase(),
+            pivots: sorter.pivots(),
+            highlights: sorter.highlights(),
+            links: sorter.links(),
+            estimated_steps: sorter.estimated_steps(len),
+            target: sorter.target(),
+            finalized: sorter.finalized(),
+            regions: sorter.regions(),
+            structure: sorter.structure().map(Structure::into_owned),
+        }
+    }
+}
+
+/// What the window asks of the worker.
+enum Command {
+    Load(Box<dyn Sorter>), // Sort with another sorter, fitting the array to its input limit.
+    Input(Vec<usize>),     // Sort these numbers from the start.
+    Reset,                 // Start the sorter over on the current array.
+    Run,                   // Take a step every `STEP_DELAY` until paused or finished.
+    Pause,
+    Step,
+}
+
+/// The state of the worker, posted after every command and every step.
+pub(crate) struct Update {
+    pub commands: u64, // Commands the worker had handled when it posted the update.
+    pub numbers: Vec<usize>, // The array.
+    pub view: SorterView, // The sorter.
+    pub event: Option<Event>, // The step taken, if the update follows one.
+    pub finished: bool, // Whether that step finished the sort.
+}
+
+/// A sorting session running on a worker thread, so slow steps never hold up the window.
+/// The window sends commands and draws the updates the worker posts back.
+pub(crate) struct Session {
+    commands: Sender<Command>,
+    updates: Receiver<Update>,
+    sent: u64,      // Commands sent so far.
+    restarted: u64, // Commands sent up to the last one that restarted the sort.
+    handled: u64,   // Commands the worker reported handled in the last update.
+}
+
+impl Session {
+    /// Starts a worker sorting `numbers` with `sorter`. It stays paused until told otherwise.
+    /// # Arguments
+    /// * `muted` - Whether the worker's steps are silent, for sessions without a window.
+    pub fn start(sorter: Box<dyn Sorter>, numbers: Vec<usize>, muted: bool) -> Self {
+        let (commands, received) = mpsc::channel();
+        // Holding a single update makes the worker wait for a slow window, instead of
+        // queueing copies of the array it may never draw.
+        let (posted, updates) = mpsc::sync_channel(1);
+        thread::spawn(move || {
+            let worker = Worker::new(sorter, numbers);
+            if muted {
+                sound::muted(|| worker.work(&received, &posted));
+            } else {
+                worker.work(&received, &posted);
+            }
+        });
+        Self {
+            commands,
+            updates,
+            sent: 0,
+            restarted: 0,
+            handled: 0,
+        }
+    }
+
+    /// Replaces the sorter. The array is cut to the input limit of the new sorter,
+    /// or restored from the input if the new sorter takes more of it.
+    pub fn load(&mut self, sorter: Box<dyn Sorter>) {
+        self.send(Command::Load(sorter));
+        self.restarted = self.sent;
+    }
+
+    /// Replaces the input and restarts the sorter on it.
+    pub fn set_input(&mut self, numbers: Vec<usize>) {
+        self.send(Command::Input(numbers));
+        self.restarted = self.sent;
+    }
+
+    /// Pauses and restarts the sorter on the current array.
+    pub fn reset(&mut self) {
+        self.send(Command::Reset);
+        self.restarted = self.sent;
+    }
+
+    /// Steps continuously until paused or finished.
+    pub fn run(&mut self) {
+        self.send(Command::Run);
+    }
+
+    /// Stops stepping after the current step.
+    pub fn pause(&mut self) {
+        self.send(Command::Pause);
+    }
+
+    /// Takes a single step.
+    pub fn step(&mut self) {
+        self.send(Command::Step);
+    }
+
+    /// Returns the next update posted by the worker, skipping those about a sort
+    /// that has since been restarted.
+    pub fn next_update(&mut self) -> Option<Update> {
+        loop {
+            let update = self.updates.try_recv().ok()?;
+            self.handled = update.commands;
+            if update.commands >= self.restarted {
+                return Some(update);
+            }
+        }
+    }
+
+    /// Returns the number of commands sent so far.
+    pub fn sent(&self) -> u64 {
+        self.sent
+    }
+
+    /// Returns the number of commands the worker has handled, as of the last update.
+    pub fn handled(&self) -> u64 {
+        self.handled
+    }
+
+    fn send(&mut self, command: Command) {
+        self.sent += 1;
+        // The worker only stops once the session is dropped.
+        let _ = self.commands.send(command);
+    }
+}
+
+/// The sorter and the array, owned by the worker thread.
+struct Worker {
+    sorter: Box<dyn Sorter>,
+    input: Vec<usize>, // The numbers to sort, before the input limit.
+    numbers: Vec<usize>,
+    running: bool,
+    handled: u64, // Commands handled so far.
+}
+
+impl Worker {
+    fn new(sorter: Box<dyn Sorter>, input: Vec<usize>) -> Self {
+        let mut worker = Self {
+            sorter,
+            numbers: input.clone(),
+            input,
+            running: false,
+            handled: 0,
+        };
+        worker.apply_input_limit();
+        worker
+    }
+
+    /// Handles commands and steps until the session is dropped.
+    fn work(mut self, commands: &Receiver<Command>, updates: &SyncSender<Update>) {
+        loop {
+            let command = if self.running {
+                match commands.try_recv() {
+                    Ok(command) => Some(command),
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Disconnected) => return,
+                }
+            } else {
+                match commands.recv() {
+                    Ok(command) => Some(command),
+                    Err(_) => return,
+                }
+            };
+            let update = match command {
+                Some(command) => self.handle(command),
+                None => {
+                    thread::sleep(STEP_DELAY);
+                    self.step()
+                }
+            };
+            if updates.send(update).is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Applies `command` and describes the state after it.
+    fn handle(&mut self, command: Command) -> Update {
+        self.handled += 1;
+        match command {
+            Command::Load(sorter) => {
+                self.sorter = sorter;
+                self.running = false;
+                self.apply_input_limit();
+            }
+            Command::Input(numbers) => {
+                self.numbers = numbers.clone();
+                self.input = numbers;
+                self.sorter.reset_state();
+                self.running = false;
+                self.apply_input_limit();
+            }
+            Command::Reset => {
+                self.sorter.reset_state();
+                self.running = false;
+            }
+            Command::Run => self.running = true,
+            Command::Pause => self.running = false,
+            Command::Step => return self.step(),
+        }
+        self.update(None, false)
+    }
+
+    /// Takes a step, stopping if it finishes the sort.
+    fn step(&mut self) -> Update {
+        let before = self.numbers.clone();
+        let finished = self.sorter.step(&mut self.numbers);
+        if finished {
+            self.running = false;
+        }
+        let event = Event::observe(self.sorter.as_ref(), &before, &self.numbers);
+        self.update(Some(event), finished)
+    }
+
+    /// Cuts the array to what the sorter can sort within `STEP_BUDGET`,
+    /// and restores the full input once a sorter without a cap is loaded.
+    fn apply_input_limit(&mut self) {
+        let limit = input_limit(self.sorter.as_ref(), self.input.len());
+        if self.numbers.len() != limit {
+            self.numbers = self.input[..limit].to_vec();
+        }
+    }
+
+    fn update(&self, event: Option<Event>, finished: bool) -> Update {
+        Update {
+            commands: self.handled,
+            numbers: self.numbers.clone(),
+            view: SorterView::of(self.sorter.as_ref(), self.numbers.len()),
+            event,
+            finished,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{bogo_sort::BogoSort, insertion_sort::InsertionSort};
+    use std::time::{Duration, Instant};
+
+    /// Waits for the worker to handle every command sent so far.
+    fn settle(session: &mut Session) -> Vec<Update> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut updates = Vec::new();
+        while session.handled() < session.sent() {
+            assert!(Instant::now() < deadline, "the worker did not answer");
+            updates.extend(session.next_update());
+            thread::sleep(Duration::from_millis(1));
+        }
+        updates
+    }
+
+    #[test]
+    fn steps_arrive_as_updates_and_resets_drop_stale_ones() {
+        let mut session = Session::start(Box::new(InsertionSort::new()), vec![3, 1, 2], true);
+        session.step();
+        let updates = settle(&mut session);
+        let last = updates.last().unwrap();
+        assert_eq!(last.numbers, [1, 3, 2]);
+        assert_eq!(last.event.as_ref().unwrap().changes, [(0, 1), (1, 3)]);
+        assert_eq!(last.view.comparisons, 1);
+
+        session.run();
+        session.set_input(vec![2, 1]);
+        let updates = settle(&mut session);
+        assert!(updates.iter().all(|update| update.numbers.len() == 2));
+        assert_eq!(updates.last().unwrap().view.comparisons, 0);
+    }
+
+    #[test]
+    fn slow_sorters_get_a_capped_input() {
+        let mut session = Session::start(Box::new(InsertionSort::new()), (0..40).collect(), true);
+        session.load(Box::new(BogoSort::new()));
+        let updates = settle(&mut session);
+        let capped = updates.last().unwrap().numbers.len();
+        assert!(capped < 40);
+
+        session.load(Box::new(InsertionSort::new()));
+        assert_eq!(settle(&mut session).last().unwrap().numbers.len(), 40);
+    }
+}
@@ -0,0 +1,165 @@
+use super::export::{Canvas, MAX_EXPORT_STEPS};
+use crate::algorithms::Sorter;
+use crate::sound;
+use eframe::epaint::{Color32, Hsva};
+
+/// Most rows a space-time diagram keeps. Past that, every other row is dropped
+/// and only every other step is recorded from then on, so a long sort still fits whole.
+pub const MAX_ROWS: usize = 2_048;
+
+/// The "sorting spacetime" of a run: the array after every step, one row per step,
+/// drawn with a color per value so every algorithm leaves its own pattern.
+#[derive(Default)]
+pub(crate) struct Spacetime {
+    rows: Vec<Vec<usize>>, // The input, then the array after every `stride`-th step.
+    stride: usize,         // Steps between two rows, doubled whenever the rows are thinned.
+    steps: usize,          // Steps taken since the input.
+    low: usize,            // Smallest and largest value of the input, the ends of the color scale.
+    high: usize,
+    finished: bool, // Whether the last step is recorded, so the next step starts a new run.
+}
+
+impl Spacetime {
+    /// Runs `sorter` headless on a copy of `numbers` and records every step.
+    pub fn run(numbers: &[usize], sorter: &mut dyn Sorter) -> Self {
+        let mut spacetime = Self::default();
+        let mut array = numbers.to_vec();
+        spacetime.start(&array);
+        sound::muted(|| {
+            for step in 1..=MAX_EXPORT_STEPS {
+                let finished = sorter.step(&mut array);
+                spacetime.record(&array, finished || step == MAX_EXPORT_STEPS);
+                if finished {
+                    break;
+                }
+            }
+        });
+        spacetime
+    }
+
+    /// Forgets the recorded run.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Records `numbers` as the input of a new run, unless a run of as many numbers
+    /// is still being recorded.
+    pub fn start(&mut self, numbers: &[usize]) {
+        let recording = self.rows.first().map(Vec::len) == Some(numbers.len());
+        if !recording || self.finished {
+            *self = Self {
+                rows: vec![numbers.to_vec()],
+                stride: 1,
+                steps: 0,
+                low: numbers.iter().copied().min().unwrap_or(0),
+                high: numbers.iter().copied().max().unwrap_or(0),
+                finished: false,
+            };
+        }
+    }
+
+    /// Records the array after a step.
+    /// # Arguments
+    /// * `numbers` - The array after the step.
+    /// * `last` - Whether it is the last step, which is kept even between two recorded rows.
+    pub fn record(&mut self, numbers: &[usize], last: bool) {
+        if self.rows.is_empty() || self.finished {
+            return;
+        }
+        self.steps += 1;
+        self.finished = last;
+        if self.steps.is_multiple_of(self.stride) || last {
+            self.rows.push(numbers.to_vec());
+        }
+        if self.rows.len() > MAX_ROWS {
+            self.rows = self.rows.iter().step_by(2).cloned().collect();
+            self.stride *= 2;
+        }
+    }
+
+    /// Returns the recorded rows, the input first.
+    pub fn rows(&self) -> &[Vec<usize>] {
+        &self.rows
+    }
+
+    /// Returns the number of steps between two rows.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the number of steps recorded since the input.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Returns the color of `value`, from red for the smallest value of the input
+    /// through the rainbow to violet for the largest.
+    pub fn color(&self, value: usize) -> Color32 {
+        let span = self.high.saturating_sub(self.low).max(1);
+        let position = value.clamp(self.low, self.high) - self.low;
+        let hue = 0.8 * position as f32 / span as f32;
+        Hsva::new(hue, 0.85, 0.95, 1.0).into()
+    }
+
+    /// Paints the diagram on a canvas about `width` pixels wide, one cell per value.
+    /// Short runs get taller rows, so the picture stays roughly square.
+    pub fn to_canvas(&self, width: u32) -> Canvas {
+        let columns = self.rows.first().map_or(0, Vec::len).max(1) as u32;
+        let cell = (width / columns).max(1);
+        let row_height = (width / self.rows.len().max(1) as u32).clamp(1, cell);
+        let mut canvas = Canvas::new(
+            columns * cell,
+            self.rows.len().max(1) as u32 * row_height,
+            Color32::BLACK,
+        );
+        for (y, row) in self.rows.iter().enumerate() {
+            for (x, &value) in row.iter().take(columns as usize).enumerate() {
+                let color = self.color(value).to_array();
+                for dy in 0..row_height {
+                    let line = (y as u32 * row_height + dy) * canvas.width;
+                    let start = (line + x as u32 * cell) as usize * 4;
+                    for pixel in canvas.pixels[start..start + cell as usize * 4].chunks_exact_mut(4)
+                    {
+                        pixel.copy_from_slice(&color);
+                    }
+                }
+            }
+        }
+        canvas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::insertion_sort::InsertionSort;
+
+    #[test]
+    fn long_runs_are_thinned_to_every_other_step() {
+        let mut spacetime = Spacetime::default();
+        spacetime.start(&[2, 1]);
+        for step in 1..=MAX_ROWS * 3 {
+            spacetime.record(&[step, 0], step == MAX_ROWS * 3);
+        }
+
+        let rows = spacetime.rows();
+        assert!(rows.len() <= MAX_ROWS);
+        assert_eq!(spacetime.stride(), 4);
+        assert_eq!(rows[0], [2, 1]);
+        assert_eq!(rows[1][0], 4);
+        assert_eq!(rows[rows.len() - 1][0], MAX_ROWS * 3);
+    }
+
+    #[test]
+    fn a_run_ends_with_the_sorted_array() {
+        let mut sorter = InsertionSort::new();
+        let spacetime = Spacetime::run(&[3, 1, 2], &mut sorter);
+        assert_eq!(spacetime.rows().first().unwrap(), &[3, 1, 2]);
+        assert_eq!(spacetime.rows().last().unwrap(), &[1, 2, 3]);
+        assert_eq!(spacetime.rows().len(), spacetime.steps() + 1);
+
+        let canvas = spacetime.to_canvas(30);
+        assert_eq!(canvas.width, 30);
+        assert_eq!(canvas.height as usize % spacetime.rows().len(), 0);
+    }
+}